@@ -0,0 +1,62 @@
+//! Compares [`hast::format`] and [`hast::format_streaming`] on a multi-megabyte input, made of
+//! the `tests/*.html` files repeated.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hast::{format, format_streaming, Configuration};
+
+/// Size of the input, in bytes.
+const SIZE: usize = 4 * 1024 * 1024;
+
+const FIXTURES: &[&str] = &[
+    include_str!("../tests/basic.html"),
+    include_str!("../tests/blocks.html"),
+    include_str!("../tests/comments.html"),
+    include_str!("../tests/inline.html"),
+    include_str!("../tests/mixed-block-content.html"),
+    include_str!("../tests/pre.html"),
+    include_str!("../tests/raw-text.html"),
+];
+
+fn input() -> String {
+    let mut input = String::with_capacity(SIZE);
+
+    for fixture in FIXTURES.iter().cycle() {
+        if input.len() >= SIZE {
+            break;
+        }
+
+        input.push_str(fixture);
+        input.push('\n');
+    }
+
+    input
+}
+
+fn bench_format(c: &mut Criterion) {
+    let input = input();
+    let config = Configuration::default();
+
+    let mut group = c.benchmark_group("format");
+
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("format", input.len()),
+        &input,
+        |b, input| {
+            b.iter(|| format(input, &config).unwrap());
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("format_streaming", input.len()),
+        &input,
+        |b, input| b.iter(|| format_streaming(input, &config).unwrap()),
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_format);
+criterion_main!(benches);
@@ -0,0 +1,139 @@
+//! [`Arbitrary`] owned nodes, for property testing (with the `proptest` feature).
+//!
+//! Only trees that parse back as-is from their HTML (see [`crate::ast::to_html`]) are generated:
+//! no optional end tags, raw text, whitespace nor blank lines, and text made of plain words.
+
+use proptest::{
+    collection::vec,
+    prelude::{prop, prop_oneof, Arbitrary, BoxedStrategy, Strategy},
+};
+
+use crate::ast::{
+    comment::Comment,
+    element::{
+        attribute::{Attribute, AttributeValue, Quote},
+        Element,
+    },
+    Node,
+};
+
+const NORMAL_ELEMENTS: &[&str] = &["div", "section", "span", "b", "em"];
+const VOID_ELEMENTS: &[&str] = &["br", "img", "input"];
+const ATTRIBUTES: &[&str] = &["class", "id", "title", "data-value", "disabled"];
+
+/// Words separated by single spaces.
+const TEXT: &str = "[a-z]{1,8}( [a-z]{1,8}){0,3}";
+
+impl Arbitrary for Node<'static> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): ()) -> Self::Strategy {
+        let leaf = prop_oneof![
+            TEXT.prop_map(|text| Node::Text(text.into())),
+            TEXT.prop_map(|content| Node::Comment(Comment(format!(" {content} ").into()))),
+            (prop::sample::select(VOID_ELEMENTS), attributes()).prop_map(|(name, attributes)| {
+                Node::Element(Element::Void {
+                    name: name.into(),
+                    attributes,
+                })
+            }),
+        ];
+
+        leaf.prop_recursive(4, 64, 6, |node| {
+            (
+                prop::sample::select(NORMAL_ELEMENTS),
+                attributes(),
+                siblings(node),
+            )
+                .prop_map(|(name, attributes, content)| {
+                    Node::Element(Element::Normal {
+                        name: name.into(),
+                        attributes,
+                        content,
+                    })
+                })
+        })
+        .boxed()
+    }
+}
+
+/// E.g. a document or element content, adjacent text nodes are merged as they would be when
+/// parsed.
+fn siblings(
+    node: impl Strategy<Value = Node<'static>>,
+) -> impl Strategy<Value = Vec<Node<'static>>> {
+    vec(node, 0..6).prop_map(|nodes| {
+        let mut merged: Vec<Node<'static>> = Vec::with_capacity(nodes.len());
+
+        for node in nodes {
+            match (merged.last_mut(), node) {
+                (Some(Node::Text(previous)), Node::Text(text)) => {
+                    *previous = format!("{previous} {text}").into();
+                }
+                (_, node) => merged.push(node),
+            }
+        }
+
+        merged
+    })
+}
+
+/// Distinct attributes, double-quoted except boolean ones.
+fn attributes() -> impl Strategy<Value = Vec<Attribute<'static>>> {
+    prop::sample::subsequence(ATTRIBUTES, 0..=3).prop_flat_map(|names| {
+        names
+            .into_iter()
+            .map(|name| {
+                "[a-z0-9 ]{0,8}".prop_map(move |text| Attribute {
+                    name: name.into(),
+                    value: (name != "disabled").then(|| AttributeValue {
+                        text: text.into(),
+                        quote: Quote::Double,
+                    }),
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prelude::any, prop_assert_eq, proptest, test_runner::TestCaseError};
+
+    use crate::{
+        ast::{to_html, Node},
+        format, format_streaming, parse, Configuration,
+    };
+
+    use super::siblings;
+
+    proptest! {
+        #[test]
+        fn test_round_trip(nodes in siblings(any::<Node>())) {
+            let html = to_html(&nodes);
+
+            prop_assert_eq!(parse(&html), Ok(nodes));
+
+            let config = Configuration::default();
+            let fail = |error: anyhow::Error| TestCaseError::fail(error.to_string());
+
+            let once = format(&html, &config).map_err(fail)?;
+
+            prop_assert_eq!(format(&once, &config).map_err(fail)?, once);
+        }
+
+        #[test]
+        fn test_format_streaming(nodes in siblings(any::<Node>())) {
+            let html = to_html(&nodes);
+
+            let config = Configuration::default();
+            let fail = |error: anyhow::Error| TestCaseError::fail(error.to_string());
+
+            prop_assert_eq!(
+                format_streaming(&html, &config).map_err(fail)?,
+                format(&html, &config).map_err(fail)?
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until},
+    character::complete::{char, satisfy},
+    combinator::{not, recognize},
+    sequence::{delimited, pair},
+    Parser,
+};
+
+use super::error::ParseResult;
+
+/// Malformed markup declaration (e.g. `<! foo>`, `<? foo>` or `</ >`), kept as-is.
+///
+/// Holds everything between `<` and `>`, see
+/// <https://html.spec.whatwg.org/multipage/parsing.html#bogus-comment-state>.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BogusComment<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Cow<'a, str>);
+
+impl<'a> BogusComment<'a> {
+    /// Should be attempted last, once proper comments, doctypes, etc. failed to parse.
+    pub fn parse(input: &str) -> ParseResult<'_, BogusComment<'_>> {
+        delimited(
+            char('<'),
+            recognize(pair(
+                alt((
+                    // Unterminated comments and CDATA sections run to eof instead.
+                    recognize(pair(char('!'), not(alt((tag("--"), tag("[CDATA[")))))),
+                    tag("?"),
+                    recognize(pair(
+                        char('/'),
+                        not(satisfy(|char: char| char.is_ascii_alphabetic())),
+                    )),
+                )),
+                take_until(">"),
+            )),
+            char('>'),
+        )
+        .map(|bogus_comment: &str| BogusComment(bogus_comment.into()))
+        .parse(input)
+    }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> BogusComment<'static> {
+        BogusComment(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl fmt::Display for BogusComment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}>", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BogusComment;
+
+    #[test]
+    fn test_parse_bogus_comment() {
+        assert_eq!(
+            BogusComment::parse("<! foo>!"),
+            Ok(("!", BogusComment("! foo".into())))
+        );
+        assert_eq!(
+            BogusComment::parse("</ >"),
+            Ok(("", BogusComment("/ ".into())))
+        );
+        assert_eq!(
+            BogusComment::parse("</>"),
+            Ok(("", BogusComment("/".into())))
+        );
+        assert_eq!(
+            BogusComment::parse("<? foo>"),
+            Ok(("", BogusComment("? foo".into())))
+        );
+
+        assert!(BogusComment::parse("</div>").is_err());
+        assert!(BogusComment::parse("<!-- foo >").is_err());
+        assert!(BogusComment::parse("<div>").is_err());
+        assert!(BogusComment::parse("<! unterminated").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use nom::{
+    bytes::complete::{tag, take_until},
+    sequence::delimited,
+    Parser,
+};
+
+use super::error::ParseResult;
+
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#cdata-sections>.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CData<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Cow<'a, str>);
+
+impl<'a> CData<'a> {
+    pub fn parse(input: &str) -> ParseResult<'_, CData<'_>> {
+        delimited(tag("<![CDATA["), take_until("]]>"), tag("]]>"))
+            .map(|cdata: &str| CData(cdata.into()))
+            .parse(input)
+    }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> CData<'static> {
+        CData(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl fmt::Display for CData<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<![CDATA[{}]]>", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CData;
+
+    #[test]
+    fn test_parse_cdata() {
+        assert_eq!(CData::parse("<![CDATA[]]>"), Ok(("", CData("".into()))));
+
+        assert_eq!(
+            CData::parse("<![CDATA[ a < b && c > d ]]>"),
+            Ok(("", CData(" a < b && c > d ".into())))
+        );
+
+        assert_eq!(
+            CData::parse("<![CDATA[\n  <not-an-element>\n]]>"),
+            Ok(("", CData("\n  <not-an-element>\n".into())))
+        );
+
+        assert!(CData::parse("<![CDATA[ unterminated").is_err());
+    }
+}
@@ -1,30 +1,81 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
 use nom::{
+    branch::alt,
     bytes::complete::{tag, take_until},
     sequence::delimited,
-    IResult, Parser,
+    Parser,
 };
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub struct Comment<'a>(pub &'a str);
+use super::error::ParseResult;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Cow<'a, str>);
 
 impl<'a> Comment<'a> {
-    pub fn parse(input: &str) -> IResult<&str, Comment> {
-        delimited(tag("<!--"), take_until("-->"), tag("-->"))
-            .map(|input: &str| {
-                // TODO: Revisit this.
-
-                let multiline = input.trim().contains('\n');
-
-                if multiline {
-                    let start = input.find('\n').unwrap() + 1;
-                    let end = input.rfind('\n').unwrap();
-                    &input[start..end]
-                } else {
-                    input.trim()
-                }
-            })
-            .map(Comment)
-            .parse(input)
+    /// Keeps everything between `<!--` and `-->`, see [`Comment::content`].
+    ///
+    /// `--` is allowed in the content (although not by the spec), `<!-->` and `<!--->` are
+    /// empty comments.
+    pub fn parse(input: &str) -> ParseResult<'_, Comment<'_>> {
+        alt((
+            alt((tag("<!-->"), tag("<!--->"))).map(|_| Comment("".into())),
+            delimited(tag("<!--"), take_until("-->"), tag("-->"))
+                .map(|content: &str| Comment(content.into())),
+        ))
+        .parse(input)
+    }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> Comment<'static> {
+        Comment(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// The content, trimmed unless conditional.
+    ///
+    /// Only the blank lines around the content of multiline comments are removed, the
+    /// indentation of the first non-blank line (and thus the others) is kept.
+    pub fn content(&self) -> &str {
+        let content = self.0.as_ref();
+
+        // Whitespace and inner markup matter in conditional comments, keep them as-is.
+        if is_conditional(content) {
+            return content;
+        }
+
+        if !content.trim().contains('\n') {
+            return content.trim();
+        }
+
+        let leading = &content[..content.len() - content.trim_start().len()];
+        let start = leading.rfind('\n').map_or(0, |index| index + 1);
+
+        content[start..].trim_end()
+    }
+
+    /// Whether this is a downlevel / conditional comment (e.g. `<!--[if IE]>...<![endif]-->`).
+    pub fn is_conditional(&self) -> bool {
+        is_conditional(&self.0)
+    }
+
+    /// Whether this is `<!-- hast-ignore -->` (or `<!-- prettier-ignore -->`), the next sibling
+    /// node is then kept as-is when formatting.
+    pub fn is_ignore(&self) -> bool {
+        matches!(self.content(), "hast-ignore" | "prettier-ignore")
+    }
+}
+
+fn is_conditional(content: &str) -> bool {
+    let content = content.trim();
+
+    content.starts_with("[if") || content.ends_with("[endif]")
+}
+
+impl fmt::Display for Comment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!--{}-->", self.0)
     }
 }
 
@@ -34,42 +85,94 @@ mod tests {
 
     #[test]
     fn test_parse_inline_comment() {
-        assert_eq!(
-            Comment::parse("<!-- My comment -->"),
-            Ok(("", Comment("My comment")))
-        );
+        let (remaining, comment) = Comment::parse("<!-- My comment -->").unwrap();
+
+        assert_eq!(remaining, "");
+        assert_eq!(comment, Comment(" My comment ".into()));
+        assert_eq!(comment.content(), "My comment");
     }
 
     #[test]
     fn test_parse_single_line_comment() {
-        assert_eq!(
-            Comment::parse(
-                "<!--
+        let (_, comment) = Comment::parse(
+            "<!--
                     My comment
-                -->"
-            ),
-            Ok(("", Comment("My comment")))
-        );
+                -->",
+        )
+        .unwrap();
+
+        assert_eq!(comment.content(), "My comment");
     }
 
     #[test]
     fn test_parse_multiline_comment() {
-        assert_eq!(
-            Comment::parse(
-                "<!--
+        let (_, comment) = Comment::parse(
+            "<!--
                     My
                     multiline
                     comment
-                -->"
-            ),
+                -->",
+        )
+        .unwrap();
+
+        assert_eq!(
+            comment.content(),
+            concat!(
+                "                    My\n",
+                "                    multiline\n",
+                "                    comment"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_dashes() {
+        let content = |input| Comment::parse(input).unwrap().1.content().to_string();
+
+        assert_eq!(content("<!-- -- -->"), "--");
+        assert_eq!(content("<!-- a -- b -->"), "a -- b");
+        assert_eq!(content("<!--\n  a --\n  -- b\n-->"), "  a --\n  -- b");
+        assert_eq!(content("<!-----\n--- -->"), "---\n---");
+
+        assert_eq!(Comment::parse("<!-->a"), Ok(("a", Comment("".into()))));
+        assert_eq!(Comment::parse("<!--->a"), Ok(("a", Comment("".into()))));
+        assert_eq!(Comment::parse("<!---->a"), Ok(("a", Comment("".into()))));
+    }
+
+    #[test]
+    fn test_parse_blank_lines() {
+        let content = |input| Comment::parse(input).unwrap().1.content().to_string();
+
+        assert_eq!(content("<!--\n\n    a\n\n  b\n  \n\n-->"), "    a\n\n  b");
+        assert_eq!(content("<!-- a\n  b -->"), " a\n  b");
+        assert_eq!(content("<!--\n  \n\n-->"), "");
+        assert_eq!(content("<!--\n-->"), "");
+    }
+
+    #[test]
+    fn test_parse_conditional_comment() {
+        assert_eq!(
+            Comment::parse(r#"<!--[if lt IE 9]> <link  href="ie.css"> <![endif]-->"#),
             Ok((
                 "",
-                Comment(concat!(
-                    "                    My\n",
-                    "                    multiline\n",
-                    "                    comment"
-                ))
+                Comment(r#"[if lt IE 9]> <link  href="ie.css"> <![endif]"#.into())
             ))
         );
+
+        assert_eq!(
+            Comment::parse("<!-- <![endif]-->"),
+            Ok(("", Comment(" <![endif]".into())))
+        );
+        assert_eq!(Comment(" <![endif]".into()).content(), " <![endif]");
+
+        assert!(Comment(" [if IE]>".into()).is_conditional());
+        assert!(!Comment("if IE".into()).is_conditional());
+    }
+
+    #[test]
+    fn test_is_ignore() {
+        assert!(Comment(" hast-ignore ".into()).is_ignore());
+        assert!(Comment("prettier-ignore".into()).is_ignore());
+        assert!(!Comment(" hast-ignore-next ".into()).is_ignore());
     }
 }
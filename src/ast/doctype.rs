@@ -1,42 +1,86 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
 use nom::{
     branch::alt,
-    bytes::complete::{tag, tag_no_case, take_while1},
+    bytes::complete::{tag, tag_no_case, take_till, take_until, take_while, take_while1},
     character::complete::char,
-    combinator::opt,
-    sequence::{delimited, preceded, tuple},
-    IResult, Parser,
+    combinator::{opt, recognize},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    Parser,
 };
 
-use super::util::is_ascii_whitespace;
+use super::{error::ParseResult, util::is_ascii_whitespace};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub struct Doctype {
-    pub legacy: bool,
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#the-doctype>.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "camelCase")
+)]
+pub enum Doctype<'a> {
+    /// `<!DOCTYPE html>`
+    Html,
+    /// `<!DOCTYPE html SYSTEM "about:legacy-compat">`
+    Legacy,
+    /// Any other doctype (e.g. XHTML's), kept as-is: holds what follows `<!DOCTYPE `, including
+    /// its internal subset if any (e.g. `html [ <!ENTITY nbsp "&#160;"> ]`).
+    Other(#[cfg_attr(feature = "serde", serde(borrow))] Cow<'a, str>),
 }
 
-impl Doctype {
-    pub fn parse(input: &str) -> IResult<&str, Self> {
-        delimited(
+impl<'a> Doctype<'a> {
+    pub fn parse(input: &str) -> ParseResult<'_, Doctype<'_>> {
+        preceded(
             tuple((
                 char('<'),
                 char('!'),
                 tag_no_case("DOCTYPE"),
                 take_while1(is_ascii_whitespace),
-                tag_no_case("html"),
             )),
-            opt(preceded(
-                take_while1(is_ascii_whitespace),
-                parse_legacy_string,
-            ))
-            .map(|legacy| legacy.is_some()),
-            char('>'),
+            alt((
+                terminated(
+                    preceded(
+                        tag_no_case("html"),
+                        opt(preceded(
+                            take_while1(is_ascii_whitespace),
+                            parse_legacy_string,
+                        )),
+                    ),
+                    pair(take_while(is_ascii_whitespace), char('>')),
+                )
+                .map(|legacy| {
+                    if legacy.is_some() {
+                        Doctype::Legacy
+                    } else {
+                        Doctype::Html
+                    }
+                }),
+                terminated(
+                    recognize(pair(
+                        take_till(|char| matches!(char, '>' | '[')),
+                        opt(parse_internal_subset),
+                    )),
+                    pair(take_while(is_ascii_whitespace), char('>')),
+                )
+                .map(|doctype: &str| Doctype::Other(doctype.trim_end().into())),
+            )),
         )
-        .map(|legacy| Doctype { legacy })
         .parse(input)
     }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> Doctype<'static> {
+        match self {
+            Self::Html => Doctype::Html,
+            Self::Legacy => Doctype::Legacy,
+            Self::Other(doctype) => Doctype::Other(Cow::Owned(doctype.into_owned())),
+        }
+    }
 }
 
-fn parse_legacy_string(input: &str) -> IResult<&str, ()> {
+fn parse_legacy_string(input: &str) -> ParseResult<'_, ()> {
     tuple((
         tag_no_case("SYSTEM"),
         take_while1(is_ascii_whitespace),
@@ -49,6 +93,30 @@ fn parse_legacy_string(input: &str) -> IResult<&str, ()> {
     .parse(input)
 }
 
+/// `[ ... ]`, which may contain `>` (e.g. `<!ENTITY foo "bar">`) unlike the rest of the doctype.
+fn parse_internal_subset(input: &str) -> ParseResult<'_, &str> {
+    recognize(delimited(
+        char('['),
+        many0(alt((
+            delimited(char('"'), take_until("\""), char('"')),
+            delimited(char('\''), take_until("'"), char('\'')),
+            take_while1(|char| !matches!(char, ']' | '"' | '\'')),
+        ))),
+        char(']'),
+    ))
+    .parse(input)
+}
+
+impl fmt::Display for Doctype<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Html => f.write_str("<!DOCTYPE html>"),
+            Self::Legacy => f.write_str(r#"<!DOCTYPE html SYSTEM "about:legacy-compat">"#),
+            Self::Other(doctype) => write!(f, "<!DOCTYPE {doctype}>"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{parse_legacy_string, Doctype};
@@ -68,14 +136,86 @@ mod tests {
 
     #[test]
     fn test_parse_doctype() {
+        assert_eq!(Doctype::parse("<!DOCTYPE html>"), Ok(("", Doctype::Html)));
+
         assert_eq!(
-            Doctype::parse("<!DOCTYPE html>"),
-            Ok(("", Doctype { legacy: false }))
+            Doctype::parse("<!DOCTYPE html SYSTEM 'about:legacy-compat'>"),
+            Ok(("", Doctype::Legacy))
         );
+    }
+
+    #[test]
+    fn test_parse_doctype_case_and_whitespace() {
+        for input in [
+            "<!doctype html>",
+            "<!DoCtYpE HtMl>",
+            "<!DOCTYPE html >",
+            "<!DOCTYPE\n  html\n>",
+        ] {
+            assert_eq!(Doctype::parse(input), Ok(("", Doctype::Html)), "{input:?}");
+        }
+
+        for input in [
+            r#"<!doctype html system "about:legacy-compat">"#,
+            "<!DOCTYPE  html  SYSTEM  'about:legacy-compat' >",
+        ] {
+            assert_eq!(
+                Doctype::parse(input),
+                Ok(("", Doctype::Legacy)),
+                "{input:?}"
+            );
+        }
 
         assert_eq!(
-            Doctype::parse("<!DOCTYPE html SYSTEM 'about:legacy-compat'>"),
-            Ok(("", Doctype { legacy: true }))
+            Doctype::parse("<!doctype HTML >").map(|(_, doctype)| doctype.to_string()),
+            Ok("<!DOCTYPE html>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_other_doctype() {
+        let xhtml = concat!(
+            r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN""#,
+            "\n",
+            r#"  "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd""#,
+        );
+
+        assert_eq!(
+            Doctype::parse(&format!("<!DOCTYPE {xhtml} >")),
+            Ok(("", Doctype::Other(xhtml.into())))
         );
+        assert_eq!(
+            Doctype::Other(xhtml.into()).to_string(),
+            format!("<!DOCTYPE {xhtml}>")
+        );
+
+        assert_eq!(
+            Doctype::parse("<!DOCTYPE html SYSTEM 'other'>"),
+            Ok(("", Doctype::Other("html SYSTEM 'other'".into())))
+        );
+
+        assert!(Doctype::parse("<!DOCTYPE>").is_err());
+    }
+
+    #[test]
+    fn test_parse_internal_subset() {
+        let doctype = concat!(
+            "html [\n",
+            "  <!ENTITY nbsp \"&#160;\">\n",
+            "  <!ENTITY brackets '[]>'>\n",
+            "]",
+        );
+        let input = format!("<!DOCTYPE {doctype} ><p>");
+
+        assert_eq!(
+            Doctype::parse(&input),
+            Ok(("<p>", Doctype::Other(doctype.into())))
+        );
+        assert_eq!(
+            Doctype::Other(doctype.into()).to_string(),
+            format!("<!DOCTYPE {doctype}>")
+        );
+
+        assert!(Doctype::parse("<!DOCTYPE html [ <!ENTITY a \"b\">").is_err());
     }
 }
@@ -0,0 +1,78 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use crate::escape::escape_attribute_value;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub name: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub value: Option<AttributeValue<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributeValue<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub text: Cow<'a, str>,
+    /// Quote used in the source.
+    pub quote: Quote,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Quote {
+    Double,
+    Single,
+    Unquoted,
+}
+
+impl Attribute<'_> {
+    /// See [`Node::into_owned`](crate::ast::Node::into_owned).
+    pub fn into_owned(self) -> Attribute<'static> {
+        Attribute {
+            name: Cow::Owned(self.name.into_owned()),
+            value: self.value.map(AttributeValue::into_owned),
+        }
+    }
+}
+
+impl AttributeValue<'_> {
+    /// Whether this value may be written without quotes, see
+    /// <https://html.spec.whatwg.org/multipage/syntax.html#unquoted>.
+    pub fn is_unquotable(&self) -> bool {
+        !self.text.is_empty()
+            && !self.text.contains(|char: char| {
+                char.is_ascii_whitespace() || matches!(char, '"' | '\'' | '=' | '<' | '>' | '`')
+            })
+    }
+
+    /// See [`Node::into_owned`](crate::ast::Node::into_owned).
+    pub fn into_owned(self) -> AttributeValue<'static> {
+        AttributeValue {
+            text: Cow::Owned(self.text.into_owned()),
+            quote: self.quote,
+        }
+    }
+}
+
+impl fmt::Display for Attribute<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        let Some(value @ AttributeValue { text, quote }) = &self.value else {
+            return Ok(());
+        };
+
+        match quote {
+            Quote::Single => write!(f, "='{}'", escape_attribute_value(text, Some('\''))),
+            Quote::Unquoted if value.is_unquotable() => {
+                write!(f, "={}", escape_attribute_value(text, None))
+            }
+            _ => write!(f, "=\"{}\"", escape_attribute_value(text, Some('"'))),
+        }
+    }
+}
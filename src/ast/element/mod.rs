@@ -1,45 +1,207 @@
+use alloc::{borrow::Cow, string::ToString, vec, vec::Vec};
+use core::fmt;
+
 use nom::{
-    bytes::complete::{take_while, take_while1},
-    character::complete::char,
-    combinator::{fail, opt},
+    bytes::complete::{take_till, take_while, take_while1},
+    character::complete::{char, satisfy},
+    combinator::{cut, opt},
     multi::many0,
     sequence::{delimited, preceded, tuple},
-    IResult, Parser,
+    Parser,
 };
 
-use crate::ast::Node;
+use crate::{
+    ast::{Context, Node},
+    parse::{ParseErrorKind, ParseOptions},
+};
 
-use self::util::{parse_attribute, parse_tag_name};
+use self::{
+    attribute::{Attribute, AttributeValue, Quote},
+    util::{parse_attribute, parse_tag_name},
+};
 
-use super::util::is_ascii_whitespace;
+use super::{
+    error::{Error, ParseResult},
+    util::is_ascii_whitespace,
+};
 
+pub mod attribute;
 mod util;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "camelCase"))]
 pub enum Element<'a> {
     Normal {
-        name: &'a str,
-        attributes: Vec<(&'a str, Option<&'a str>)>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        name: Cow<'a, str>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        attributes: Vec<Attribute<'a>>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
         content: Vec<Node<'a>>,
     },
     Void {
-        name: &'a str,
-        attributes: Vec<(&'a str, Option<&'a str>)>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        name: Cow<'a, str>,
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        attributes: Vec<Attribute<'a>>,
     },
 }
 
 impl<'a> Element<'a> {
-    pub fn parse_end_tag(input: &'a str) -> IResult<&'a str, &'a str> {
+    /// Attributes and solidus (e.g. `</div foo>` or `</div/>`) are ignored, as browsers do.
+    pub fn parse_end_tag(input: &'a str) -> ParseResult<'a, &'a str> {
         delimited(
             tuple((char('<'), char('/'))),
             parse_tag_name,
-            tuple((take_while(is_ascii_whitespace), char('>'))),
+            tuple((
+                opt(tuple((
+                    satisfy(|char| is_ascii_whitespace(char) || char == '/'),
+                    take_till(|char| char == '>'),
+                ))),
+                char('>'),
+            )),
         )
         .parse(input)
     }
 
-    pub fn parse(input: &'a str) -> IResult<&'a str, Self> {
-        let (input, (name, attributes, self_closing)) = delimited(
+    /// Name of the start tag beginning `input`, if any.
+    pub(super) fn peek_start_tag_name(input: &'a str) -> Option<&'a str> {
+        preceded(char('<'), parse_tag_name)
+            .parse(input)
+            .ok()
+            .map(|(_, name)| name)
+    }
+
+    /// Once a start tag has been parsed, any subsequent error is a [`nom::Err::Failure`].
+    pub fn parse(input: &'a str) -> ParseResult<'a, Self> {
+        Self::parse_with_options(input, &ParseOptions::default())
+    }
+
+    /// See [`Element::parse`].
+    pub fn parse_with_options(input: &'a str, options: &ParseOptions<'_>) -> ParseResult<'a, Self> {
+        Self::parse_with(input, Context::root(options))
+    }
+
+    /// See [`Element::parse`], `context` is the one of the enclosing element's content.
+    pub(super) fn parse_with(input: &'a str, context: Context<'_, '_>) -> ParseResult<'a, Self> {
+        let options = context.options;
+        let start = input;
+
+        let (input, (name, attributes, self_closing)) = Self::parse_start_tag(input)?;
+
+        // A `<template>` holds a document fragment, never void even if (incorrectly) self-closed.
+        if self_closing && name.eq_ignore_ascii_case("template") {
+            return Ok((
+                input,
+                Self::Normal {
+                    name: name.into(),
+                    attributes,
+                    content: vec![],
+                },
+            ));
+        }
+
+        let context = Context {
+            foreign: context.foreign
+                || name.eq_ignore_ascii_case("svg")
+                || name.eq_ignore_ascii_case("math"),
+            ..context
+        };
+        let foreign = context.foreign;
+
+        // A stray `/` is ignored on HTML elements (e.g. `<div/>` is `<div>`), see
+        // <https://html.spec.whatwg.org/multipage/syntax.html#start-tags>.
+        let self_closing =
+            self_closing && (foreign || is_component(name) || options.self_closing_tags);
+
+        if options.is_void_element(name) || self_closing {
+            return Ok((
+                input,
+                Self::Void {
+                    name: name.into(),
+                    attributes,
+                },
+            ));
+        }
+
+        if options.is_raw_text_element(name) || is_escapable_raw_text_element(name) {
+            let (input, mut text) = Self::parse_raw_text(input, name)?;
+
+            // Whitespace is significant in `<textarea>`, not in `<title>`.
+            if name.eq_ignore_ascii_case("title") {
+                text = text.trim();
+            }
+
+            return Ok((
+                input,
+                Self::Normal {
+                    name: name.into(),
+                    attributes,
+                    content: if text.is_empty() {
+                        vec![]
+                    } else {
+                        vec![Node::Text(text.into())]
+                    },
+                },
+            ));
+        }
+
+        if context.depth >= options.max_depth {
+            return Err(nom::Err::Failure(Error::new(
+                start,
+                ParseErrorKind::MaxDepthExceeded,
+            )));
+        }
+
+        let (input, content) =
+            cut(|input| Node::parse_many_with(input, context.child(name))).parse(input)?;
+
+        if has_optional_end_tag(name) {
+            let remaining = match Self::parse_end_tag(input) {
+                Ok((remaining, end_name)) if end_name.eq_ignore_ascii_case(name) => remaining,
+                // Implicitly closed by the parent's end tag, a start tag or eof.
+                _ => input,
+            };
+
+            return Ok((
+                remaining,
+                Self::Normal {
+                    name: name.into(),
+                    attributes,
+                    content,
+                },
+            ));
+        }
+
+        let (remaining, end_name) = cut(Self::parse_end_tag).parse(input)?;
+
+        if name != end_name {
+            return Err(nom::Err::Failure(Error::new(
+                input,
+                ParseErrorKind::MismatchedEndTag {
+                    expected: name.to_string(),
+                    found: end_name.to_string(),
+                },
+            )));
+        }
+
+        Ok((
+            remaining,
+            Self::Normal {
+                name: name.into(),
+                attributes,
+                content,
+            },
+        ))
+    }
+
+    /// Name, attributes and whether the start tag is self-closing (e.g. `<div/>`), regardless of
+    /// the element it starts.
+    pub(super) fn parse_start_tag(
+        input: &'a str,
+    ) -> ParseResult<'a, (&'a str, Vec<Attribute<'a>>, bool)> {
+        delimited(
             char('<'),
             tuple((
                 parse_tag_name,
@@ -51,51 +213,400 @@ impl<'a> Element<'a> {
             )),
             char('>'),
         )
-        .parse(input)?;
-
-        if matches!(
-            name.to_ascii_lowercase().as_str(),
-            "area"
-                | "base"
-                | "br"
-                | "col"
-                | "embed"
-                | "hr"
-                | "img"
-                | "input"
-                | "link"
-                | "meta"
-                | "param"
-                | "source"
-                | "track"
-                | "wbr"
-        ) || self_closing
-        {
-            return Ok((input, Self::Void { name, attributes }));
+        .parse(input)
+    }
+
+    /// Consume input verbatim up to the (case-insensitive) end tag matching `name`,
+    /// the end tag itself is consumed but not returned.
+    fn parse_raw_text(input: &'a str, name: &str) -> ParseResult<'a, &'a str> {
+        let mut index = 0;
+
+        while let Some(delta) = input.get(index..).and_then(|input| input.find("</")) {
+            index += delta;
+
+            if let Ok((remaining, end_name)) = Self::parse_end_tag(&input[index..]) {
+                if end_name.eq_ignore_ascii_case(name) {
+                    return Ok((remaining, &input[..index]));
+                }
+            }
+
+            index += 2;
         }
 
-        let (input, content) = Node::parse_many(input)?;
+        Err(nom::Err::Failure(Error::new(
+            &input[input.len()..],
+            ParseErrorKind::UnexpectedEof,
+        )))
+    }
+}
+
+impl<'a> Element<'a> {
+    pub fn name(&self) -> &str {
+        let (Self::Normal { name, .. } | Self::Void { name, .. }) = self;
 
-        let (input, end_name) = Self::parse_end_tag(input)?;
+        name
+    }
 
-        if name != end_name {
-            return fail(input);
+    /// Whether this element was written self-closed (e.g. `<MyComponent/>`) rather than being a
+    /// void element (e.g. `<input>`, with or without a `/`), it is then written back as such.
+    pub fn is_self_closed(&self) -> bool {
+        self.is_self_closed_with_options(&ParseOptions::default())
+    }
+
+    /// See [`Element::is_self_closed`], also considering
+    /// [`ParseOptions::extra_void_elements`] as void.
+    pub fn is_self_closed_with_options(&self, options: &ParseOptions<'_>) -> bool {
+        matches!(self, Self::Void { name, .. } if !options.is_void_element(name))
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>] {
+        let (Self::Normal { attributes, .. } | Self::Void { attributes, .. }) = self;
+
+        attributes
+    }
+
+    /// Value of the attribute `name` (compared case-insensitively), `Some(None)` if it has none
+    /// (e.g. `disabled`) and `None` if it is absent.
+    ///
+    /// The first one is used when repeated, as browsers do.
+    pub fn attr(&self, name: &str) -> Option<Option<&str>> {
+        self.attributes()
+            .iter()
+            .find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+            .map(|attribute| attribute.value.as_ref().map(|value| value.text.as_ref()))
+    }
+
+    /// Whether the attribute `name` (compared case-insensitively) is present, with or without a
+    /// value.
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attr(name).is_some()
+    }
+
+    /// Child nodes, none for void elements.
+    pub fn content(&self) -> &[Node<'a>] {
+        match self {
+            Self::Normal { content, .. } => content,
+            Self::Void { .. } => &[],
         }
+    }
 
-        Ok((
-            input,
+    /// Same as [`Element::content`].
+    pub fn children(&self) -> &[Node<'a>] {
+        self.content()
+    }
+
+    /// See [`Element::children`], the slice being empty for void elements (nodes can't be added
+    /// to them).
+    pub fn children_mut(&mut self) -> &mut [Node<'a>] {
+        match self {
+            Self::Normal { content, .. } => content,
+            Self::Void { .. } => &mut [],
+        }
+    }
+
+    /// See [`Node::into_owned`].
+    pub fn into_owned(self) -> Element<'static> {
+        let owned_attributes = |attributes: Vec<Attribute>| {
+            attributes.into_iter().map(Attribute::into_owned).collect()
+        };
+
+        match self {
             Self::Normal {
                 name,
                 attributes,
                 content,
+            } => Element::Normal {
+                name: Cow::Owned(name.into_owned()),
+                attributes: owned_attributes(attributes),
+                content: content.into_iter().map(Node::into_owned).collect(),
             },
-        ))
+            Self::Void { name, attributes } => Element::Void {
+                name: Cow::Owned(name.into_owned()),
+                attributes: owned_attributes(attributes),
+            },
+        }
+    }
+
+    /// See [`Node::canonicalize`].
+    pub fn canonicalize(&self) -> Element<'a> {
+        self.canonicalize_with(false)
+    }
+
+    pub(crate) fn canonicalize_with(&self, preformatted: bool) -> Element<'a> {
+        let (Self::Normal { name, .. } | Self::Void { name, .. }) = self;
+
+        // Most likely a component (e.g. `<MyComponent>`) or a foreign element (e.g. `<svg:rect>`).
+        let case_sensitive = name.contains(':')
+            || (name.contains(|char: char| char.is_ascii_uppercase())
+                && name.contains(|char: char| char.is_ascii_lowercase()));
+
+        let name = if case_sensitive || !name.contains(|char: char| char.is_ascii_uppercase()) {
+            name.clone()
+        } else {
+            Cow::Owned(name.to_ascii_lowercase())
+        };
+
+        let mut attributes: Vec<_> = self
+            .attributes()
+            .iter()
+            .map(|Attribute { name, value }| Attribute {
+                name: name.clone(),
+                value: value.as_ref().map(|value| AttributeValue {
+                    text: value.text.clone(),
+                    quote: Quote::Double,
+                }),
+            })
+            .collect();
+        attributes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        match self {
+            Self::Normal { content, .. } => {
+                let preformatted = preformatted
+                    || is_preformatted_element(&name)
+                    || is_raw_text_element(&name)
+                    || is_escapable_raw_text_element(&name);
+
+                Element::Normal {
+                    content: content
+                        .iter()
+                        .filter(|node| !matches!(node, Node::BlankLines(_)))
+                        .map(|node| node.canonicalize_with(preformatted))
+                        .collect(),
+                    name,
+                    attributes,
+                }
+            }
+            Self::Void { .. } => Element::Void { name, attributes },
+        }
+    }
+
+    /// Every node nested in this element, depth-first (i.e. in source order).
+    pub fn descendants(&self) -> impl Iterator<Item = &Node<'a>> + '_ {
+        let mut stack = vec![self.content().iter()];
+
+        core::iter::from_fn(move || loop {
+            let node = stack.last_mut()?.next();
+
+            match node {
+                Some(node) => {
+                    if let Node::Element(element) = node {
+                        stack.push(element.content().iter());
+                    }
+
+                    return Some(node);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        })
+    }
+}
+
+/// Elements that cannot have any content (and thus no end tag).
+///
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#void-elements>.
+pub fn is_void_element(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "param"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Not a standard HTML element but a custom element (e.g. `<my-element>`), a framework component
+/// (e.g. `<MyComponent>`) or namespaced (e.g. `<svg:rect>`), which templates commonly self-close.
+pub fn is_component(name: &str) -> bool {
+    name.contains(['-', ':']) || name.contains(|char: char| char.is_ascii_uppercase())
+}
+
+/// Elements whose end tag may be omitted (a practical subset of the spec's rules).
+///
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#optional-tags>.
+pub fn has_optional_end_tag(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "p" | "li" | "dt" | "dd" | "option" | "tr" | "td" | "th"
+    )
+}
+
+/// Whether an element `name` (see [`has_optional_end_tag`]) is implicitly closed by a following
+/// `start` tag.
+pub fn is_closed_by_start_tag(name: &str, start: &str) -> bool {
+    let start = start.to_ascii_lowercase();
+
+    match name.to_ascii_lowercase().as_str() {
+        "p" => matches!(
+            start.as_str(),
+            "address"
+                | "article"
+                | "aside"
+                | "blockquote"
+                | "details"
+                | "div"
+                | "dl"
+                | "fieldset"
+                | "figcaption"
+                | "figure"
+                | "footer"
+                | "form"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "header"
+                | "hgroup"
+                | "hr"
+                | "main"
+                | "menu"
+                | "nav"
+                | "ol"
+                | "p"
+                | "pre"
+                | "section"
+                | "table"
+                | "ul"
+        ),
+        "li" => start == "li",
+        "dt" | "dd" => matches!(start.as_str(), "dt" | "dd"),
+        "option" => matches!(start.as_str(), "option" | "optgroup"),
+        "tr" => start == "tr",
+        "td" | "th" => matches!(start.as_str(), "td" | "th" | "tr"),
+        _ => false,
+    }
+}
+
+/// Elements whose whitespace is significant.
+pub fn is_preformatted_element(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "pre" | "listing" | "textarea"
+    )
+}
+
+/// Inline-level (phrasing) elements, whitespace between them and text is significant.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/HTML/Inline_elements>.
+pub fn is_inline_element(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "a" | "abbr"
+            | "b"
+            | "bdi"
+            | "bdo"
+            | "button"
+            | "cite"
+            | "code"
+            | "data"
+            | "dfn"
+            | "em"
+            | "i"
+            | "img"
+            | "input"
+            | "kbd"
+            | "label"
+            | "mark"
+            | "q"
+            | "s"
+            | "samp"
+            | "select"
+            | "small"
+            | "span"
+            | "strong"
+            | "sub"
+            | "sup"
+            | "time"
+            | "u"
+            | "var"
+    )
+}
+
+/// Elements whose content is raw text (i.e. never parsed as markup).
+///
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#raw-text-elements>.
+pub fn is_raw_text_element(name: &str) -> bool {
+    matches!(name.to_ascii_lowercase().as_str(), "script" | "style")
+}
+
+/// Elements whose content is text, character references are allowed but tags are not.
+///
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#escapable-raw-text-elements>.
+pub fn is_escapable_raw_text_element(name: &str) -> bool {
+    matches!(name.to_ascii_lowercase().as_str(), "textarea" | "title")
+}
+
+/// Compact HTML, spec void elements are written without a solidus (e.g. `<br>`).
+impl fmt::Display for Element<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_html(f, &ParseOptions::default())
+    }
+}
+
+impl Element<'_> {
+    /// See [`crate::ast::to_html_with_options`].
+    pub(crate) fn write_html(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        options: &ParseOptions<'_>,
+    ) -> fmt::Result {
+        let (Self::Normal {
+            name, attributes, ..
+        }
+        | Self::Void { name, attributes }) = self;
+
+        write!(f, "<{name}")?;
+
+        for attribute in attributes {
+            write!(f, " {attribute}")?;
+        }
+
+        match self {
+            Self::Normal { content, .. } => {
+                f.write_str(">")?;
+
+                for node in content {
+                    match node {
+                        // Raw text isn't escaped, escapable raw text is kept as-is too.
+                        Node::Text(text)
+                            if options.is_raw_text_element(name)
+                                || is_escapable_raw_text_element(name) =>
+                        {
+                            f.write_str(text)?;
+                        }
+                        node => node.write_html(f, options)?,
+                    }
+                }
+
+                write!(f, "</{name}>")
+            }
+            Self::Void { .. } if options.is_void_element(name) => f.write_str(">"),
+            Self::Void { .. } => f.write_str("/>"),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Element;
+    use crate::{ast::Node, ParseOptions};
+
+    use super::{
+        attribute::{Attribute, AttributeValue, Quote},
+        Element,
+    };
 
     #[test]
     fn test_parse_void_element() {
@@ -104,7 +615,7 @@ mod tests {
             Ok((
                 "",
                 Element::Void {
-                    name: "input",
+                    name: "input".into(),
                     attributes: vec![],
                 }
             ))
@@ -115,8 +626,95 @@ mod tests {
             Ok((
                 "",
                 Element::Void {
-                    name: "input",
-                    attributes: vec![("type", Some("text")), ("required", None)],
+                    name: "input".into(),
+                    attributes: vec![
+                        Attribute {
+                            name: "type".into(),
+                            value: Some(AttributeValue {
+                                text: "text".into(),
+                                quote: Quote::Double
+                            })
+                        },
+                        Attribute {
+                            name: "required".into(),
+                            value: None
+                        }
+                    ],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_end_tag() {
+        for end_tag in [
+            "</div>",
+            "</div >",
+            "</div foo>",
+            "</div/>",
+            "</div\n  foo=\"bar\" / >",
+        ] {
+            assert_eq!(
+                Element::parse_end_tag(end_tag),
+                Ok(("", "div")),
+                "{end_tag}"
+            );
+        }
+
+        assert_eq!(Element::parse_end_tag("</div foo>a>"), Ok(("a>", "div")));
+        assert!(Element::parse_end_tag("</div foo").is_err());
+        assert!(Element::parse_end_tag("</ div>").is_err());
+
+        assert_eq!(
+            Node::parse_many("<div><p>a</p foo><span>b</span/></div x>c"),
+            Node::parse_many("<div><p>a</p><span>b</span></div>c")
+        );
+    }
+
+    #[test]
+    fn test_parse_attribute_containing_gt() {
+        let attribute = |name: &'static str, text: &'static str, quote| Attribute {
+            name: name.into(),
+            value: Some(AttributeValue {
+                text: text.into(),
+                quote,
+            }),
+        };
+
+        assert_eq!(
+            Element::parse(r#"<p title="a > b" data-x='>'>text</p>"#),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![
+                        attribute("title", "a > b", Quote::Double),
+                        attribute("data-x", ">", Quote::Single)
+                    ],
+                    content: vec![Node::Text("text".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse(r#"<img alt="a > b"/>"#),
+            Ok((
+                "",
+                Element::Void {
+                    name: "img".into(),
+                    attributes: vec![attribute("alt", "a > b", Quote::Double)],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<p title=a>b>c</p>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![attribute("title", "a", Quote::Unquoted)],
+                    content: vec![Node::Text("b>c".into())],
                 }
             ))
         );
@@ -129,7 +727,7 @@ mod tests {
             Ok((
                 "",
                 Element::Void {
-                    name: "MyComponent",
+                    name: "MyComponent".into(),
                     attributes: vec![],
                 }
             ))
@@ -140,10 +738,482 @@ mod tests {
             Ok((
                 "",
                 Element::Void {
-                    name: "MyComponent",
-                    attributes: vec![(":attr", Some("yes")),],
+                    name: "MyComponent".into(),
+                    attributes: vec![Attribute {
+                        name: ":attr".into(),
+                        value: Some(AttributeValue {
+                            text: "yes".into(),
+                            quote: Quote::Double
+                        })
+                    }],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_self_closing_tags() {
+        let options = ParseOptions {
+            self_closing_tags: true,
+            ..ParseOptions::default()
+        };
+        let parse =
+            |input| Element::parse_with_options(input, &options).map(|(_, element)| element);
+
+        assert_eq!(
+            parse("<div><div/><span/>a</div>"),
+            Ok(Element::Normal {
+                name: "div".into(),
+                attributes: vec![],
+                content: vec![
+                    Node::Element(Element::Void {
+                        name: "div".into(),
+                        attributes: vec![],
+                    }),
+                    Node::Element(Element::Void {
+                        name: "span".into(),
+                        attributes: vec![],
+                    }),
+                    Node::Text("a".into()),
+                ],
+            })
+        );
+
+        assert!(parse("<div/>").unwrap().is_self_closed());
+        assert!(parse("<MyComponent/>").unwrap().is_self_closed());
+        assert!(!parse("<input/>").unwrap().is_self_closed());
+        assert!(!parse("<input>").unwrap().is_self_closed());
+        assert!(!parse("<div></div>").unwrap().is_self_closed());
+
+        // The `/` is ignored otherwise.
+        assert!(!Element::parse("<div/></div>").unwrap().1.is_self_closed());
+
+        let options = ParseOptions {
+            extra_void_elements: &["x-void"],
+            ..ParseOptions::default()
+        };
+        let (_, nodes) = Node::parse_many_with_options("<x-void><x-void/>", &options).unwrap();
+
+        for node in &nodes {
+            let Node::Element(element) = node else {
+                unreachable!();
+            };
+
+            assert!(element.is_self_closed());
+            assert!(!element.is_self_closed_with_options(&options));
+        }
+
+        assert_eq!(crate::to_html(&nodes), "<x-void/><x-void/>");
+        assert_eq!(
+            crate::to_html_with_options(&nodes, &options),
+            "<x-void><x-void>"
+        );
+    }
+
+    #[test]
+    fn test_parse_foreign_self_closing_element() {
+        let element = |name: &'static str, content| Element::Normal {
+            name: name.into(),
+            attributes: vec![],
+            content,
+        };
+        let void = |name: &'static str| {
+            Node::Element(Element::Void {
+                name: name.into(),
+                attributes: vec![],
+            })
+        };
+
+        assert_eq!(
+            Element::parse("<svg><circle/></svg>"),
+            Ok(("", element("svg", vec![void("circle")])))
+        );
+        assert_eq!(
+            Element::parse("<svg/>"),
+            Ok((
+                "",
+                Element::Void {
+                    name: "svg".into(),
+                    attributes: vec![]
+                }
+            ))
+        );
+        assert_eq!(
+            Element::parse("<math><mspace/></math>"),
+            Ok(("", element("math", vec![void("mspace")])))
+        );
+
+        assert_eq!(
+            Element::parse("<div/>text</div>"),
+            Ok(("", element("div", vec![Node::Text("text".into())])))
+        );
+        assert_eq!(
+            Element::parse("<svg><foreignObject><p/>text</p></foreignObject></svg>"),
+            Ok((
+                "",
+                element(
+                    "svg",
+                    vec![Node::Element(element(
+                        "foreignObject",
+                        vec![Node::Element(element("p", vec![Node::Text("text".into())]))]
+                    ))]
+                )
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<my-element/>"),
+            Ok((
+                "",
+                Element::Void {
+                    name: "my-element".into(),
+                    attributes: vec![]
                 }
             ))
         );
     }
+
+    #[test]
+    fn test_attr() {
+        let (_, element) =
+            Element::parse(r#"<INPUT Type="checkbox" checked value='' type="radio">"#).unwrap();
+
+        assert_eq!(element.name(), "INPUT");
+        assert_eq!(element.attr("type"), Some(Some("checkbox")));
+        assert_eq!(element.attr("CHECKED"), Some(None));
+        assert_eq!(element.attr("value"), Some(Some("")));
+        assert_eq!(element.attr("disabled"), None);
+
+        assert!(element.has_attr("checked"));
+        assert!(element.has_attr("value"));
+        assert!(!element.has_attr("disabled"));
+
+        let (_, element) = Element::parse("<p hidden>a</p>").unwrap();
+
+        assert_eq!(element.name(), "p");
+        assert_eq!(element.attr("hidden"), Some(None));
+        assert!(!element.has_attr("id"));
+    }
+
+    #[test]
+    fn test_children() {
+        let (_, mut element) = Element::parse("<br>").unwrap();
+
+        assert!(element.children().is_empty());
+        assert!(element.children_mut().is_empty());
+
+        let (_, mut element) = Element::parse("<p>a<b>b</b></p>").unwrap();
+
+        assert_eq!(element.children().len(), 2);
+        assert_eq!(element.children()[0], Node::Text("a".into()));
+        assert!(matches!(&element.children()[1], Node::Element(child) if child.name() == "b"));
+
+        element.children_mut()[0] = Node::Text("c".into());
+
+        assert_eq!(element.content()[0], Node::Text("c".into()));
+    }
+
+    #[test]
+    fn test_parse_namespaced_names() {
+        let input = r##"<svg:rect xlink:href="#x"/>"##;
+
+        assert_eq!(
+            Element::parse(input),
+            Ok((
+                "",
+                Element::Void {
+                    name: "svg:rect".into(),
+                    attributes: vec![Attribute {
+                        name: "xlink:href".into(),
+                        value: Some(AttributeValue {
+                            text: "#x".into(),
+                            quote: Quote::Double,
+                        }),
+                    }],
+                }
+            ))
+        );
+
+        let input = concat!(
+            r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink">"#,
+            r##"<svg:rect XLink:Href="#x"/><svg:g xml:lang="en"></svg:g>"##,
+            "</svg>",
+        );
+        let (_, nodes) = Node::parse_many(input).unwrap();
+
+        assert_eq!(crate::to_html(&nodes), input);
+    }
+
+    #[test]
+    fn test_descendants() {
+        let (_, element) =
+            Element::parse("<ul><li>a<ul><li>b</li><li>c<br></li></ul></li><li>d</li></ul>")
+                .unwrap();
+
+        let names: Vec<_> = element
+            .descendants()
+            .map(|node| match node {
+                Node::Element(element) => element.name(),
+                Node::Text(text) => text,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            ["li", "a", "ul", "li", "b", "li", "c", "br", "li", "d"]
+        );
+    }
+
+    #[test]
+    fn test_parse_template() {
+        for input in [
+            "<template></template>",
+            "<template/>",
+            "<template>\n\n</template>",
+        ] {
+            assert_eq!(
+                Element::parse(input),
+                Ok((
+                    "",
+                    Element::Normal {
+                        name: "template".into(),
+                        attributes: vec![],
+                        content: vec![],
+                    }
+                ))
+            );
+        }
+
+        assert_eq!(
+            Element::parse("<template><br></template>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "template".into(),
+                    attributes: vec![],
+                    content: vec![Node::Element(Element::Void {
+                        name: "br".into(),
+                        attributes: vec![],
+                    })],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_raw_text_element() {
+        assert_eq!(
+            Element::parse("<script>a < b && c > d</script>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "script".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("a < b && c > d".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<style>a > b { color: red; }</STYLE>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "style".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("a > b { color: red; }".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<script>let div = `</div>`;</script >"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "script".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("let div = `</div>`;".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse(r#"<script src="main.js"></script>"#),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "script".into(),
+                    attributes: vec![Attribute {
+                        name: "src".into(),
+                        value: Some(AttributeValue {
+                            text: "main.js".into(),
+                            quote: Quote::Double
+                        })
+                    }],
+                    content: vec![],
+                }
+            ))
+        );
+
+        assert!(Element::parse("<script>a < b").is_err());
+    }
+
+    #[test]
+    fn test_parse_escapable_raw_text_element() {
+        assert_eq!(
+            Element::parse("<textarea><div></textarea>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "textarea".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("<div>".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<textarea>\n  a < b\n\n  c > d\n</textarea>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "textarea".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("\n  a < b\n\n  c > d\n".into())],
+                }
+            ))
+        );
+
+        assert_eq!(
+            Element::parse("<title> <b>Title</b> </title>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "title".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("<b>Title</b>".into())],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_preformatted_element() {
+        assert_eq!(
+            Element::parse("<pre>\n  a\n\n  <b> b </b>\n</pre>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "pre".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Text("\n  a\n\n  ".into()),
+                        Node::Element(Element::Normal {
+                            name: "b".into(),
+                            attributes: vec![],
+                            content: vec![Node::Text(" b ".into())],
+                        }),
+                        Node::Text("\n".into()),
+                    ],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_optional_end_tag() {
+        let li = |text: &'static str| Element::Normal {
+            name: "li".into(),
+            attributes: vec![],
+            content: vec![Node::Text(text.into())],
+        };
+
+        assert_eq!(
+            Element::parse("<ul><li>a<li>b\n  <li>c</li></ul>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "ul".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Element(li("a")),
+                        Node::Element(li("b")),
+                        Node::Element(li("c")),
+                    ],
+                }
+            ))
+        );
+
+        let p = |text: &'static str| {
+            Node::Element(Element::Normal {
+                name: "p".into(),
+                attributes: vec![],
+                content: vec![Node::Text(text.into())],
+            })
+        };
+
+        assert_eq!(
+            Node::parse_many("<p>a<p>b<div>c</div><p>d"),
+            Ok((
+                "",
+                vec![
+                    p("a"),
+                    p("b"),
+                    Node::Element(Element::Normal {
+                        name: "div".into(),
+                        attributes: vec![],
+                        content: vec![Node::Text("c".into())],
+                    }),
+                    p("d"),
+                ]
+            ))
+        );
+
+        // Not closed by phrasing content.
+        assert_eq!(
+            Element::parse("<p>a<span>b</span></p>"),
+            Ok((
+                "",
+                Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Text("a".into()),
+                        Node::Element(Element::Normal {
+                            name: "span".into(),
+                            attributes: vec![],
+                            content: vec![Node::Text("b".into())],
+                        }),
+                    ],
+                }
+            ))
+        );
+
+        // End tags are case-insensitive.
+        assert_eq!(Node::parse_many("<p>a</P>"), Ok(("", vec![p("a")])));
+        assert_eq!(
+            Node::parse_many("<ul><li>a</LI><li>b</ul>"),
+            Ok((
+                "",
+                vec![Node::Element(Element::Normal {
+                    name: "ul".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Element(Element::Normal {
+                            name: "li".into(),
+                            attributes: vec![],
+                            content: vec![Node::Text("a".into())],
+                        }),
+                        Node::Element(Element::Normal {
+                            name: "li".into(),
+                            attributes: vec![],
+                            content: vec![Node::Text("b".into())],
+                        }),
+                    ],
+                })]
+            ))
+        );
+    }
 }
@@ -1,19 +1,30 @@
+use alloc::string::ToString;
+
 use nom::{
     branch::alt,
     bytes::complete::{take_till, take_until, take_while, take_while1},
-    character::complete::char,
-    combinator::opt,
+    character::complete::{char, satisfy},
+    combinator::{opt, recognize},
     sequence::{delimited, pair, preceded, tuple},
-    IResult,
+    Parser,
+};
+
+use crate::{
+    ast::{
+        error::{Error, ParseResult},
+        util::is_ascii_whitespace,
+    },
+    parse::ParseErrorKind,
 };
 
-use crate::ast::util::is_ascii_whitespace;
+use super::attribute::{Attribute, AttributeValue, Quote};
 
 /// See <https://html.spec.whatwg.org/multipage/syntax.html#attributes-2>.
-pub fn parse_attribute_name(input: &str) -> IResult<&str, &str> {
+pub fn parse_attribute_name(input: &str) -> ParseResult<'_, &str> {
     take_while1(|char: char| {
         !matches!(char,
-        '\u{007F}'..='\u{009F}'
+        '\u{0000}'..='\u{001F}'
+        | '\u{007F}'..='\u{009F}'
         | '\u{0020}'
         | '\u{0022}'
         | '\u{0027}'
@@ -59,38 +70,87 @@ pub fn parse_attribute_name(input: &str) -> IResult<&str, &str> {
 }
 
 /// See <https://html.spec.whatwg.org/multipage/syntax.html#attributes-2>.
-pub fn parse_attribute(input: &str) -> IResult<&str, (&str, Option<&str>)> {
-    pair(
-        parse_attribute_name,
-        opt(preceded(
-            tuple((
-                take_while(is_ascii_whitespace),
-                char('='),
-                take_while(is_ascii_whitespace),
-            )),
-            alt((
-                delimited(char('"'), take_until("\""), char('"')),
-                delimited(char('\''), take_until("'"), char('\'')),
-                take_while1(|char: char| {
-                    !char.is_ascii_whitespace()
-                        && !matches!(
-                            char,
-                            '\u{0022}' | '\u{0027}' | '\u{003C}'..='\u{003E}' | '\u{0060}'
-                        )
-                }),
-            )),
+///
+/// Whitespace is allowed around the `=`, which must be followed by a value.
+pub fn parse_attribute(input: &str) -> ParseResult<'_, Attribute<'_>> {
+    let (rest, name) = parse_attribute_name(input)?;
+
+    let (rest, value) = opt(preceded(
+        tuple((
+            take_while(is_ascii_whitespace),
+            char('='),
+            take_while(is_ascii_whitespace),
         )),
-    )(input)
+        |input| {
+            parse_attribute_value(input).map_err(|_: nom::Err<Error>| {
+                nom::Err::Failure(Error::new(
+                    input,
+                    ParseErrorKind::MissingAttributeValue {
+                        name: name.to_string(),
+                    },
+                ))
+            })
+        },
+    ))
+    .parse(rest)?;
+
+    Ok((
+        rest,
+        Attribute {
+            name: name.into(),
+            value,
+        },
+    ))
+}
+
+fn parse_attribute_value(input: &str) -> ParseResult<'_, AttributeValue<'_>> {
+    alt((
+        delimited(char('"'), take_until("\""), char('"')).map(|text: &str| AttributeValue {
+            text: text.into(),
+            quote: Quote::Double,
+        }),
+        delimited(char('\''), take_until("'"), char('\'')).map(|text: &str| AttributeValue {
+            text: text.into(),
+            quote: Quote::Single,
+        }),
+        take_while1(|char: char| {
+            !char.is_ascii_whitespace()
+                && !matches!(
+                    char,
+                    '\u{0022}' | '\u{0027}' | '\u{003C}'..='\u{003E}' | '\u{0060}'
+                )
+        })
+        .map(|text: &str| AttributeValue {
+            text: text.into(),
+            quote: Quote::Unquoted,
+        }),
+    ))
+    .parse(input)
 }
 
 /// See <https://html.spec.whatwg.org/multipage/syntax.html#syntax-tag-name>.
-pub fn parse_tag_name(input: &str) -> IResult<&str, &str> {
-    take_till(|char: char| char.is_ascii_whitespace() || char == '/' || char == '>')(input)
+///
+/// Stopping at `<` keeps a failed attempt from scanning past the next tag, which would make
+/// parsing text full of `<` quadratic.
+pub fn parse_tag_name(input: &str) -> ParseResult<'_, &str> {
+    recognize(pair(
+        satisfy(|char| char.is_ascii_alphabetic()),
+        take_till(|char: char| {
+            char.is_ascii_whitespace() || char == '/' || char == '>' || char == '<'
+        }),
+    ))(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{parse_attribute, parse_attribute_name, parse_tag_name};
+    use crate::{
+        ast::{
+            element::attribute::{Attribute, AttributeValue, Quote},
+            error::Error,
+        },
+        parse::ParseErrorKind,
+    };
 
     #[test]
     fn test_parse_attribute_name() {
@@ -108,10 +168,140 @@ mod tests {
     fn test_parse_attribute() {
         assert_eq!(
             parse_attribute(r#"lang="ts" setup>"#),
-            Ok((" setup>", ("lang", Some("ts"))))
+            Ok((
+                " setup>",
+                Attribute {
+                    name: "lang".into(),
+                    value: Some(AttributeValue {
+                        text: "ts".into(),
+                        quote: Quote::Double
+                    })
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_attribute(r#"onclick='say("hi")'>"#),
+            Ok((
+                ">",
+                Attribute {
+                    name: "onclick".into(),
+                    value: Some(AttributeValue {
+                        text: r#"say("hi")"#.into(),
+                        quote: Quote::Single
+                    })
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_attribute("value=1>"),
+            Ok((
+                ">",
+                Attribute {
+                    name: "value".into(),
+                    value: Some(AttributeValue {
+                        text: "1".into(),
+                        quote: Quote::Unquoted
+                    })
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_attribute("setup>"),
+            Ok((
+                ">",
+                Attribute {
+                    name: "setup".into(),
+                    value: None
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_attribute_spaced_equals() {
+        let expected = Attribute {
+            name: "class".into(),
+            value: Some(AttributeValue {
+                text: "x".into(),
+                quote: Quote::Double,
+            }),
+        };
+
+        for input in [r#"class="x">"#, r#"class = "x">"#, "class\n=\t\"x\">"] {
+            assert_eq!(
+                parse_attribute(input),
+                Ok((">", expected.clone())),
+                "{input:?}"
+            );
+        }
+
+        assert_eq!(
+            parse_attribute("class =>"),
+            Err(nom::Err::Failure(Error::new(
+                ">",
+                ParseErrorKind::MissingAttributeValue {
+                    name: "class".to_string()
+                }
+            )))
         );
 
-        assert_eq!(parse_attribute("setup>"), Ok((">", ("setup", None))));
+        assert!(matches!(
+            parse_attribute("class = "),
+            Err(nom::Err::Failure(Error {
+                input: "",
+                kind: ParseErrorKind::MissingAttributeValue { .. }
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_parse_attribute_containing_gt() {
+        assert_eq!(
+            parse_attribute(r#"title="a > b">"#),
+            Ok((
+                ">",
+                Attribute {
+                    name: "title".into(),
+                    value: Some(AttributeValue {
+                        text: "a > b".into(),
+                        quote: Quote::Double
+                    })
+                }
+            ))
+        );
+
+        assert_eq!(
+            parse_attribute("title='>'>"),
+            Ok((
+                ">",
+                Attribute {
+                    name: "title".into(),
+                    value: Some(AttributeValue {
+                        text: ">".into(),
+                        quote: Quote::Single
+                    })
+                }
+            ))
+        );
+
+        // Ends unquoted values (and thus the tag), see
+        // <https://html.spec.whatwg.org/multipage/syntax.html#unquoted>.
+        assert_eq!(
+            parse_attribute("title=a>b>"),
+            Ok((
+                ">b>",
+                Attribute {
+                    name: "title".into(),
+                    value: Some(AttributeValue {
+                        text: "a".into(),
+                        quote: Quote::Unquoted
+                    })
+                }
+            ))
+        );
     }
 
     #[test]
@@ -124,5 +314,8 @@ mod tests {
             parse_tag_name(r#"script lang="ts">"#),
             Ok((r#" lang="ts">"#, "script"))
         );
+        assert_eq!(parse_tag_name("a<b>"), Ok(("<b>", "a")));
+        assert!(parse_tag_name("<b>").is_err());
+        assert!(parse_tag_name("1>").is_err());
     }
 }
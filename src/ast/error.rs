@@ -0,0 +1,45 @@
+use nom::error::{ErrorKind as NomErrorKind, ParseError};
+
+use crate::parse::ParseErrorKind;
+
+pub type ParseResult<'a, T> = nom::IResult<&'a str, T, Error<'a>>;
+
+/// Error produced by the AST parsers, converted to a [`crate::ParseError`] by the public API.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Error<'a> {
+    /// Remaining input at the point of failure.
+    pub input: &'a str,
+    pub kind: ParseErrorKind,
+}
+
+impl<'a> Error<'a> {
+    pub fn new(input: &'a str, kind: ParseErrorKind) -> Self {
+        Self { input, kind }
+    }
+}
+
+impl<'a> ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, _kind: NomErrorKind) -> Self {
+        Self::new(
+            input,
+            if input.is_empty() {
+                ParseErrorKind::UnexpectedEof
+            } else {
+                ParseErrorKind::InvalidSyntax
+            },
+        )
+    }
+
+    fn append(_input: &'a str, _kind: NomErrorKind, other: Self) -> Self {
+        other
+    }
+
+    /// Keep the error that went the furthest.
+    fn or(self, other: Self) -> Self {
+        if other.input.len() < self.input.len() {
+            other
+        } else {
+            self
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{line_ending, not_line_ending, space0},
+    combinator::{eof, not, recognize},
+    multi::many0,
+    sequence::{pair, preceded, terminated, tuple},
+    Parser,
+};
+
+use super::error::ParseResult;
+
+/// YAML / TOML frontmatter (e.g. `---\ntitle: Home\n---`) used by static-site generators, kept
+/// as-is.
+///
+/// Holds the lines between the two `---` fences, each with its line ending.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frontmatter<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub Cow<'a, str>);
+
+impl<'a> Frontmatter<'a> {
+    /// Should only be attempted at the very start of a document.
+    pub fn parse(input: &str) -> ParseResult<'_, Frontmatter<'_>> {
+        fn fence(input: &str) -> ParseResult<'_, &str> {
+            recognize(tuple((tag("---"), space0, alt((line_ending, eof))))).parse(input)
+        }
+
+        preceded(
+            pair(tag("---"), line_ending),
+            terminated(
+                recognize(many0(preceded(
+                    not(fence),
+                    pair(not_line_ending, line_ending),
+                ))),
+                fence,
+            ),
+        )
+        .map(|frontmatter: &str| Frontmatter(frontmatter.into()))
+        .parse(input)
+    }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> Frontmatter<'static> {
+        Frontmatter(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl fmt::Display for Frontmatter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "---\n{}---\n", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frontmatter;
+
+    #[test]
+    fn test_parse_frontmatter() {
+        assert_eq!(
+            Frontmatter::parse("---\ntitle: Home\ntags: [a, b]\n---\n<p>"),
+            Ok(("<p>", Frontmatter("title: Home\ntags: [a, b]\n".into())))
+        );
+        assert_eq!(
+            Frontmatter::parse("---\r\n\r\na = 1\r\n---"),
+            Ok(("", Frontmatter("\r\na = 1\r\n".into())))
+        );
+        assert_eq!(
+            Frontmatter::parse("---\n---\n"),
+            Ok(("", Frontmatter("".into())))
+        );
+
+        assert!(Frontmatter::parse("---\ntitle: Home\n").is_err());
+        assert!(Frontmatter::parse("--- title\n---\n").is_err());
+        assert!(Frontmatter::parse("----\n---\n").is_err());
+        assert!(Frontmatter::parse("\n---\n---\n").is_err());
+    }
+}
@@ -1,23 +1,212 @@
-use nom::{branch::alt, combinator::fail, IResult, Parser};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, ops::Range, str::FromStr};
 
-use self::{comment::Comment, doctype::Doctype, element::Element};
+use nom::{branch::alt, combinator::fail, Parser};
 
+use crate::{
+    escape::escape_text,
+    parse::{strip_bom, ParseError, ParseErrorKind, ParseOptions},
+    select::{Selector, SelectorError},
+    visit::{walk_element_mut, VisitorMut},
+};
+
+use self::{
+    bogus_comment::BogusComment,
+    cdata::CData,
+    comment::Comment,
+    doctype::Doctype,
+    element::{
+        has_optional_end_tag, is_closed_by_start_tag, is_inline_element, is_preformatted_element,
+        is_raw_text_element, Element,
+    },
+    error::ParseResult,
+    frontmatter::Frontmatter,
+    processing_instruction::ProcessingInstruction,
+    util::offset_in,
+};
+
+pub mod bogus_comment;
+pub mod cdata;
 pub mod comment;
 pub mod doctype;
 pub mod element;
-mod util;
+pub mod error;
+pub mod frontmatter;
+pub mod processing_instruction;
+pub(crate) mod util;
+
+/// Default [`ParseOptions::max_depth`], leaves room to spare on small stacks (e.g. wasm, or
+/// non-main threads).
+///
+/// Each level takes a few KiB of stack in release builds (several times more in debug builds), so
+/// this assumes a stack of at least 1 MiB, wasm's default: 128 levels fit with room to spare, 512
+/// wouldn't.
+pub const MAX_DEPTH: usize = 128;
+
+/// Where the parser is in the document, along with its options.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Context<'p, 'o> {
+    /// Whitespace is kept as-is (e.g. inside `<pre>`).
+    preformatted: bool,
+    /// Name of the element whose content is being parsed, its end tag may be implied by a start
+    /// tag (e.g. `<li>` closes a previous `<li>`).
+    parent: Option<&'p str>,
+    /// Number of elements enclosing the input, see [`ParseOptions::max_depth`].
+    depth: usize,
+    /// Inside SVG or MathML.
+    foreign: bool,
+    options: &'p ParseOptions<'o>,
+}
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+impl<'p, 'o> Context<'p, 'o> {
+    pub(crate) fn root(options: &'p ParseOptions<'o>) -> Self {
+        Self {
+            preformatted: false,
+            parent: None,
+            depth: 0,
+            foreign: false,
+            options,
+        }
+    }
+
+    /// For the content of the element named `name`.
+    fn child(self, name: &'p str) -> Self {
+        Self {
+            preformatted: self.preformatted || is_preformatted_element(name),
+            parent: Some(name),
+            depth: self.depth + 1,
+            // An HTML integration point.
+            foreign: self.foreign && !name.eq_ignore_ascii_case("foreignObject"),
+            options: self.options,
+        }
+    }
+}
+
+/// `Eq` and `Hash` are structural, e.g. nodes only differing by the order of their attributes or
+/// by insignificant whitespace are different, see [`Node::canonicalize`] to compare those.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "camelCase")
+)]
 pub enum Node<'a> {
-    Comment(Comment<'a>),
-    Doctype(Doctype),
-    Element(Element<'a>),
+    /// Blank lines separating two sibling nodes in the source.
+    BlankLines(usize),
+    BogusComment(#[cfg_attr(feature = "serde", serde(borrow))] BogusComment<'a>),
+    CData(#[cfg_attr(feature = "serde", serde(borrow))] CData<'a>),
+    Comment(#[cfg_attr(feature = "serde", serde(borrow))] Comment<'a>),
+    Doctype(#[cfg_attr(feature = "serde", serde(borrow))] Doctype<'a>),
+    Element(#[cfg_attr(feature = "serde", serde(borrow))] Element<'a>),
+    /// Only ever the first node of a document.
+    Frontmatter(#[cfg_attr(feature = "serde", serde(borrow))] Frontmatter<'a>),
+    ProcessingInstruction(#[cfg_attr(feature = "serde", serde(borrow))] ProcessingInstruction<'a>),
     // NOTE: Cannot contain an end tag.
-    Text(&'a str),
+    Text(#[cfg_attr(feature = "serde", serde(borrow))] Cow<'a, str>),
+    /// Whitespace separating two inline-level sibling nodes (text or
+    /// [inline elements](element::is_inline_element)) in the source, significant when rendered.
+    Whitespace,
 }
 
-impl From<Doctype> for Node<'_> {
-    fn from(doctype: Doctype) -> Self {
+/// Write the given nodes back to compact HTML, without any reflow or indentation (unlike
+/// [`crate::format`]).
+pub fn to_html(nodes: &[Node<'_>]) -> String {
+    to_html_with_options(nodes, &ParseOptions::default())
+}
+
+/// See [`to_html`], the void and raw text elements being those of `options` (e.g.
+/// [`ParseOptions::extra_void_elements`] are written without a solidus).
+pub fn to_html_with_options(nodes: &[Node<'_>], options: &ParseOptions<'_>) -> String {
+    struct Html<'n, 'o> {
+        node: &'n Node<'n>,
+        options: &'o ParseOptions<'o>,
+    }
+
+    impl fmt::Display for Html<'_, '_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.node.write_html(f, self.options)
+        }
+    }
+
+    nodes
+        .iter()
+        .map(|node| Html { node, options }.to_string())
+        .collect()
+}
+
+/// Compact HTML, text is escaped (unless raw).
+impl fmt::Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_html(f, &ParseOptions::default())
+    }
+}
+
+impl Node<'_> {
+    /// See [`to_html_with_options`].
+    fn write_html(&self, f: &mut fmt::Formatter<'_>, options: &ParseOptions<'_>) -> fmt::Result {
+        match self {
+            // Keep the blank lines, and thus the node, when re-parsed.
+            Self::BlankLines(count) => f.write_str(&"\n".repeat(count + 1)),
+            Self::BogusComment(bogus_comment) => fmt::Display::fmt(bogus_comment, f),
+            Self::CData(cdata) => fmt::Display::fmt(cdata, f),
+            Self::Comment(comment) => fmt::Display::fmt(comment, f),
+            Self::Doctype(doctype) => fmt::Display::fmt(doctype, f),
+            Self::Element(element) => element.write_html(f, options),
+            Self::Frontmatter(frontmatter) => fmt::Display::fmt(frontmatter, f),
+            Self::ProcessingInstruction(processing_instruction) => {
+                fmt::Display::fmt(processing_instruction, f)
+            }
+            Self::Text(text) => f.write_str(&escape_text(text)),
+            Self::Whitespace => f.write_str(" "),
+        }
+    }
+}
+
+/// Top-level nodes which own their content, so that they can outlive the input they were parsed
+/// from (see [`crate::parse`] and [`Node::into_owned`]).
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Fragment(pub Vec<Node<'static>>);
+
+impl FromStr for Fragment {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let nodes = crate::parse(input)?;
+
+        Ok(Self(nodes.into_iter().map(Node::into_owned).collect()))
+    }
+}
+
+impl fmt::Display for Fragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|node| write!(f, "{node}"))
+    }
+}
+
+impl From<Fragment> for Vec<Node<'static>> {
+    fn from(fragment: Fragment) -> Self {
+        fragment.0
+    }
+}
+
+impl<'a> From<BogusComment<'a>> for Node<'a> {
+    fn from(bogus_comment: BogusComment<'a>) -> Self {
+        Self::BogusComment(bogus_comment)
+    }
+}
+
+impl<'a> From<CData<'a>> for Node<'a> {
+    fn from(cdata: CData<'a>) -> Self {
+        Self::CData(cdata)
+    }
+}
+
+impl<'a> From<Doctype<'a>> for Node<'a> {
+    fn from(doctype: Doctype<'a>) -> Self {
         Self::Doctype(doctype)
     }
 }
@@ -28,88 +217,1227 @@ impl<'a> From<Element<'a>> for Node<'a> {
     }
 }
 
+impl<'a> From<Frontmatter<'a>> for Node<'a> {
+    fn from(frontmatter: Frontmatter<'a>) -> Self {
+        Self::Frontmatter(frontmatter)
+    }
+}
+
 impl<'a> From<Comment<'a>> for Node<'a> {
     fn from(comment: Comment<'a>) -> Self {
         Self::Comment(comment)
     }
 }
 
+impl<'a> From<ProcessingInstruction<'a>> for Node<'a> {
+    fn from(processing_instruction: ProcessingInstruction<'a>) -> Self {
+        Self::ProcessingInstruction(processing_instruction)
+    }
+}
+
+impl Node<'_> {
+    /// Byte range of this node in `input`, which it must have been parsed from.
+    ///
+    /// Computed from the position of the slices borrowed from `input`, hence `None` for owned
+    /// nodes (e.g. built or modified ones) as well as doctypes, blank lines and whitespace, which
+    /// don't borrow anything.
+    pub fn span(&self, input: &str) -> Option<Range<usize>> {
+        // Re-parse from `start`, checking that `slice` is indeed the one of this node.
+        fn reparse<'i, T>(
+            input: &'i str,
+            start: usize,
+            slice: &str,
+            parse: impl Fn(&'i str) -> ParseResult<'i, T>,
+            get: impl Fn(&T) -> &str,
+        ) -> Option<Range<usize>> {
+            let (remaining, node) = parse(input.get(start..)?).ok()?;
+
+            (get(&node).as_ptr() == slice.as_ptr()).then(|| start..input.len() - remaining.len())
+        }
+
+        match self {
+            Self::BlankLines(_) | Self::Doctype(_) | Self::Whitespace => None,
+            Self::BogusComment(BogusComment(Cow::Borrowed(bogus_comment))) => reparse(
+                input,
+                offset_in(input, bogus_comment)?.checked_sub("<".len())?,
+                bogus_comment,
+                BogusComment::parse,
+                |BogusComment(bogus_comment)| bogus_comment,
+            ),
+            Self::CData(CData(Cow::Borrowed(cdata))) => reparse(
+                input,
+                offset_in(input, cdata)?.checked_sub("<![CDATA[".len())?,
+                cdata,
+                CData::parse,
+                |CData(cdata)| cdata,
+            ),
+            Self::Comment(Comment(Cow::Borrowed(comment))) => reparse(
+                input,
+                offset_in(input, comment)?.checked_sub("<!--".len())?,
+                comment,
+                Comment::parse,
+                |Comment(comment)| comment,
+            ),
+            Self::Element(element) => {
+                let (Element::Normal {
+                    name: Cow::Borrowed(name),
+                    ..
+                }
+                | Element::Void {
+                    name: Cow::Borrowed(name),
+                    ..
+                }) = element
+                else {
+                    return None;
+                };
+
+                // Only the start tag is re-parsed, its content and end tag depend on where the
+                // element is (e.g. in `<svg>`) and on the parse options: they are located from
+                // the parsed content instead.
+                let start = offset_in(input, name)?.checked_sub("<".len())?;
+                let (remaining, (start_name, _, self_closing)) =
+                    Element::parse_start_tag(input.get(start..)?).ok()?;
+
+                if start_name.as_ptr() != name.as_ptr() {
+                    return None;
+                }
+
+                let mut end = input.len() - remaining.len();
+
+                let Element::Normal { content, .. } = element else {
+                    return Some(start..end);
+                };
+
+                // See `Element::parse_with`.
+                if self_closing && content.is_empty() && name.eq_ignore_ascii_case("template") {
+                    return Some(start..end);
+                }
+
+                if let Some(last) = content
+                    .iter()
+                    .rev()
+                    .find(|node| !matches!(node, Self::BlankLines(_) | Self::Whitespace))
+                {
+                    end = last.span(input)?.end;
+                }
+
+                // Unless implied (e.g. `<li>` without `</li>`), the end tag follows the content.
+                let rest = input[end..].trim_start_matches(|char: char| char.is_ascii_whitespace());
+
+                if let Ok((remaining, end_name)) = Element::parse_end_tag(rest) {
+                    if end_name.eq_ignore_ascii_case(name) {
+                        end = input.len() - remaining.len();
+                    }
+                }
+
+                Some(start..end)
+            }
+            Self::Frontmatter(Frontmatter(Cow::Borrowed(frontmatter))) => reparse(
+                input,
+                input.len() - strip_bom(input).len(),
+                frontmatter,
+                Frontmatter::parse,
+                |Frontmatter(frontmatter)| frontmatter,
+            ),
+            Self::ProcessingInstruction(ProcessingInstruction(Cow::Borrowed(
+                processing_instruction,
+            ))) => reparse(
+                input,
+                offset_in(input, processing_instruction)?.checked_sub("<?".len())?,
+                processing_instruction,
+                ProcessingInstruction::parse,
+                |ProcessingInstruction(processing_instruction)| processing_instruction,
+            ),
+            Self::Text(Cow::Borrowed(text)) => {
+                let start = offset_in(input, text)?;
+
+                Some(start..start + text.len())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Node<'a> {
+    /// Copy everything borrowed from the input, so that the node can outlive it.
+    pub fn into_owned(self) -> Node<'static> {
+        match self {
+            Self::BlankLines(count) => Node::BlankLines(count),
+            Self::BogusComment(bogus_comment) => Node::BogusComment(bogus_comment.into_owned()),
+            Self::CData(cdata) => Node::CData(cdata.into_owned()),
+            Self::Comment(comment) => Node::Comment(comment.into_owned()),
+            Self::Doctype(doctype) => Node::Doctype(doctype.into_owned()),
+            Self::Element(element) => Node::Element(element.into_owned()),
+            Self::Frontmatter(frontmatter) => Node::Frontmatter(frontmatter.into_owned()),
+            Self::ProcessingInstruction(processing_instruction) => {
+                Node::ProcessingInstruction(processing_instruction.into_owned())
+            }
+            Self::Text(text) => Node::Text(Cow::Owned(text.into_owned())),
+            Self::Whitespace => Node::Whitespace,
+        }
+    }
+
+    /// Normalized copy, equal (and hashing the same) to the one of a semantically equal node:
+    /// - tag names are lowercase (unless mixed-case or namespaced, e.g. `<MyComponent>`),
+    /// - attributes are sorted (stably) by name and double-quoted,
+    /// - whitespace in text is collapsed to a single space (unless preformatted or raw),
+    /// - blank lines are removed from element content.
+    pub fn canonicalize(&self) -> Node<'a> {
+        self.canonicalize_with(false)
+    }
+
+    pub(crate) fn canonicalize_with(&self, preformatted: bool) -> Node<'a> {
+        match self {
+            Self::Element(element) => Node::Element(element.canonicalize_with(preformatted)),
+            Self::Text(text) if !preformatted => {
+                let mut whitespace = false;
+
+                Node::Text(
+                    text.chars()
+                        .filter_map(|char| {
+                            let previous =
+                                core::mem::replace(&mut whitespace, char.is_ascii_whitespace());
+
+                            match (whitespace, previous) {
+                                (true, true) => None,
+                                (true, false) => Some(' '),
+                                (false, _) => Some(char),
+                            }
+                        })
+                        .collect::<String>()
+                        .into(),
+                )
+            }
+            node => node.clone(),
+        }
+    }
+
+    /// Text of this node and its descendants (like the DOM's `textContent`), with whitespace
+    /// collapsed to single spaces and trimmed.
+    ///
+    /// Block-level elements are separated by a space, unlike inline ones (e.g. `<b>a</b>b` is
+    /// `ab`). Comments, doctypes and the content of raw text elements (e.g. `<script>`) are
+    /// skipped, character references are kept as-is (e.g. `&amp;`).
+    pub fn text_content(&self) -> String {
+        fn push_text(text: &str, buffer: &mut String, separated: &mut bool) {
+            for char in text.chars() {
+                if char.is_ascii_whitespace() {
+                    *separated = true;
+                } else {
+                    if core::mem::take(separated) && !buffer.is_empty() {
+                        buffer.push(' ');
+                    }
+
+                    buffer.push(char);
+                }
+            }
+        }
+
+        fn push_node(node: &Node, buffer: &mut String, separated: &mut bool) {
+            match node {
+                Node::Text(text) | Node::CData(CData(text)) => push_text(text, buffer, separated),
+                Node::BlankLines(_) | Node::Whitespace => *separated = true,
+                Node::Element(element) if !is_raw_text_element(element.name()) => {
+                    let block = !node.is_inline_level();
+
+                    *separated |= block;
+
+                    for node in element.content() {
+                        push_node(node, buffer, separated);
+                    }
+
+                    *separated |= block;
+                }
+                _ => {}
+            }
+        }
+
+        let mut buffer = String::new();
+
+        push_node(self, &mut buffer, &mut false);
+
+        buffer
+    }
+
+    /// This node (if an element) and its descendants named `tag` (case-insensitively), in
+    /// source order.
+    pub fn find_all(&self, tag: &str) -> Vec<&Element<'a>> {
+        self.elements()
+            .filter(|element| element.name().eq_ignore_ascii_case(tag))
+            .collect()
+    }
+
+    /// This node (if an element) and its descendants matching `selector` (see
+    /// [`crate::select`]), in source order.
+    ///
+    /// # Errors
+    /// Will return an error if `selector` is not supported, see [`Selector::parse`].
+    pub fn select(&self, selector: &str) -> Result<Vec<&Element<'a>>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+
+        Ok(self
+            .elements()
+            .filter(|element| selector.matches(element))
+            .collect())
+    }
+
+    /// Apply `f` to this node (if an element) and its descendants named `tag`
+    /// (case-insensitively), e.g. to add a class to every `<a>`.
+    ///
+    /// The content of an element is visited once `f` was applied to it.
+    pub fn replace_elements(&mut self, tag: &str, f: impl FnMut(&mut Element<'a>)) {
+        struct Replace<'t, F> {
+            tag: &'t str,
+            f: F,
+        }
+
+        impl<'a, F: FnMut(&mut Element<'a>)> VisitorMut<'a> for Replace<'_, F> {
+            fn visit_element_mut(&mut self, element: &mut Element<'a>) {
+                if element.name().eq_ignore_ascii_case(self.tag) {
+                    (self.f)(element);
+                }
+
+                walk_element_mut(self, element);
+            }
+        }
+
+        Replace { tag, f }.visit_node_mut(self);
+    }
+
+    /// This node (if an element) and its descendant elements.
+    fn elements(&self) -> impl Iterator<Item = &Element<'a>> {
+        let element = match self {
+            Self::Element(element) => Some(element),
+            _ => None,
+        };
+
+        element.into_iter().chain(
+            element
+                .into_iter()
+                .flat_map(Element::descendants)
+                .filter_map(|node| match node {
+                    Self::Element(element) => Some(element),
+                    _ => None,
+                }),
+        )
+    }
+}
+
 impl<'a> Node<'a> {
     /// Consume input as text until:
     /// - a non-text node (returned in the second part of the tuple),
     /// - an end tag,
     /// - or eof.
-    fn parse_text(input: &'a str) -> IResult<&'a str, (Self, Option<Self>)> {
+    ///
+    /// Leading whitespace is trimmed unless preformatted, trailing whitespace is left to the caller.
+    fn parse_text(
+        input: &'a str,
+        context: Context<'_, '_>,
+    ) -> ParseResult<'a, (&'a str, Option<Self>)> {
         let mut index = 0;
 
-        let input = input.trim_start();
+        let input = if context.preformatted {
+            input
+        } else {
+            input.trim_start()
+        };
 
         if input.is_empty() {
             return fail(input);
         }
 
+        // Nodes running up to a terminator and the position of the next one, if already looked
+        // up: once there is none left, attempting to parse such a node would scan to eof in vain.
+        let mut terminators: [(&str, &str, Option<Option<usize>>); 3] = [
+            ("<!--", "-->", None),
+            ("<![CDATA[", "]]>", None),
+            ("<?", "?>", None),
+        ];
+
+        let mut next_gt: Option<Option<usize>> = None;
+
         loop {
             if let Some(delta) = input.get(index..).and_then(|input| input.find('<')) {
                 index += delta;
 
-                if Element::parse_end_tag(&input[index..]).is_ok() {
-                    break Ok((
-                        &input[index..],
-                        (Self::Text(input[..index].trim_end()), None),
-                    ));
+                if Self::is_end_of_content(&input[index..], context.parent) {
+                    break Ok((&input[index..], (&input[..index], None)));
+                }
+
+                if next_gt.is_none_or(|next| next.is_some_and(|next| next < index)) {
+                    next_gt = Some(input[index..].find('>').map(|delta| index + delta));
                 }
 
-                if let Ok((remaining, next)) = Self::parse_non_text(&input[index..]) {
-                    break Ok((
-                        remaining,
-                        (Self::Text(input[..index].trim_end()), Some(next)),
-                    ));
+                // Every node ends with a `>`.
+                if next_gt == Some(None) {
+                    break Ok(("", (input, None)));
+                }
+
+                let unterminated = terminators.iter_mut().any(|(opener, terminator, next)| {
+                    if !input[index..].starts_with(*opener) {
+                        return false;
+                    }
+
+                    // The terminator may overlap the opener, e.g. `<!-->` is an empty comment.
+                    let start = index + "<!".len();
+
+                    if next.is_none_or(|next| next.is_some_and(|next| next < start)) {
+                        *next = Some(input[start..].find(*terminator).map(|delta| start + delta));
+                    }
+
+                    *next == Some(None)
+                });
+
+                if unterminated {
+                    // Not worth parsing as anything else, but maybe as a bogus comment (`<? a>`).
+                    if let Ok((remaining, next)) = BogusComment::parse(&input[index..]) {
+                        break Ok((remaining, (&input[..index], Some(next.into()))));
+                    }
+
+                    index += 1;
+                    continue;
+                }
+
+                match Self::parse_non_text(&input[index..], context) {
+                    Ok((remaining, next)) => {
+                        break Ok((remaining, (&input[..index], Some(next))));
+                    }
+                    Err(error @ nom::Err::Failure(_)) => break Err(error),
+                    Err(_) => {}
                 }
 
                 index += 1;
             } else {
-                break Ok(("", (Self::Text(input.trim_end()), None)));
+                break Ok(("", (input, None)));
             }
         }
     }
 
-    fn parse_non_text(input: &'a str) -> IResult<&'a str, Self> {
+    fn parse_non_text(input: &'a str, context: Context<'_, '_>) -> ParseResult<'a, Self> {
         alt((
+            ProcessingInstruction::parse.map(Self::from),
+            CData::parse.map(Self::from),
             Comment::parse.map(Self::from),
             Doctype::parse.map(Self::from),
-            Element::parse.map(Self::from),
+            (|input| Element::parse_with(input, context)).map(Self::from),
+            BogusComment::parse.map(Self::from),
         ))
         .parse(input)
     }
 
-    /// Consume input as long as it parses into a node.
-    pub fn parse_many(input: &'a str) -> IResult<&'a str, Vec<Self>> {
-        let mut remaining = input.trim_start();
+    /// Parse the first top-level node (e.g. an element along with its content) of `input`, after
+    /// any whitespace, also returning the input following it.
+    ///
+    /// Frontmatter isn't parsed, see [`Node::parse_many`].
+    ///
+    /// # Errors
+    /// Will return an error if `input` is empty (or only whitespace), starts with an end tag or if
+    /// parsing fails.
+    pub fn parse_one(input: &'a str) -> Result<(Self, &'a str), ParseError> {
+        let trimmed = input.trim_start();
+
+        if trimmed.is_empty() {
+            return Err(ParseError::new(
+                input,
+                trimmed,
+                ParseErrorKind::UnexpectedEof,
+            ));
+        }
+
+        if Element::parse_end_tag(trimmed).is_ok() {
+            return Err(ParseError::new(
+                input,
+                trimmed,
+                ParseErrorKind::UnexpectedEndTag,
+            ));
+        }
+
+        let options = ParseOptions::default();
+        let context = Context::root(&options);
+
+        match Self::parse_non_text(trimmed, context) {
+            Ok((rest, node)) => Ok((node, rest)),
+            Err(error @ nom::Err::Failure(_)) => Err(ParseError::from_nom(input, error)),
+            Err(_) => {
+                let (_, (text, _)) = Self::parse_text(trimmed, context)
+                    .map_err(|error| ParseError::from_nom(input, error))?;
+
+                let text = text.trim_end();
+
+                Ok((Self::Text(text.into()), &trimmed[text.len()..]))
+            }
+        }
+    }
+
+    /// Consume input as long as it parses into a node, starting with the [`Frontmatter`] if any.
+    ///
+    /// Stops at a stray end tag (e.g. `</div>` without a matching start tag), which is left in the
+    /// remaining input. [`crate::parse`] reports it as
+    /// [`ParseErrorKind::UnexpectedEndTag`] instead.
+    ///
+    /// Elements may be nested up to [`MAX_DEPTH`] levels deep, see [`Node::parse_many_with_options`]
+    /// to change it.
+    pub fn parse_many(input: &'a str) -> ParseResult<'a, Vec<Self>> {
+        Self::parse_many_with_options(input, &ParseOptions::default())
+    }
+
+    /// See [`Node::parse_many`] and [`ParseOptions`].
+    pub fn parse_many_with_options(
+        input: &'a str,
+        options: &ParseOptions<'_>,
+    ) -> ParseResult<'a, Vec<Self>> {
+        let context = Context::root(options);
+
+        let Ok((rest, frontmatter)) = Frontmatter::parse(input) else {
+            return Self::parse_many_with(input, context);
+        };
+
+        let (remaining, mut nodes) = Self::parse_many_with(rest, context)?;
+
+        nodes.insert(0, frontmatter.into());
+
+        Ok((remaining, nodes))
+    }
+
+    /// See [`Node::parse_many`], up to the end of the content of `context.parent` if any.
+    pub(crate) fn parse_many_with(
+        input: &'a str,
+        context: Context<'_, '_>,
+    ) -> ParseResult<'a, Vec<Self>> {
+        let mut remaining = if context.preformatted {
+            input
+        } else {
+            input.trim_start()
+        };
+
         let mut buffer = Vec::new();
 
         loop {
-            if Element::parse_end_tag(remaining).is_ok() {
+            if remaining.is_empty() || Self::is_end_of_content(remaining, context.parent) {
                 break Ok((remaining, buffer));
             }
 
-            if remaining.is_empty() {
-                break Ok(("", buffer));
-            }
+            (remaining, ()) = Self::parse_step(remaining, context, &mut buffer)?;
+        }
+    }
 
-            if let Ok((rest, node)) = Self::parse_non_text(remaining.trim_start()) {
-                buffer.push(node);
-                remaining = rest.trim_start();
-            } else {
-                let (rest, (node, next)) = Self::parse_text(remaining)?;
+    /// Parse top-level nodes one at a time, see [`Node::parse_many`].
+    ///
+    /// `input` must not start with whitespace, nor be empty or start with an end tag.
+    pub(crate) fn parse_next(
+        input: &'a str,
+        options: &ParseOptions<'_>,
+        buffer: &mut Vec<Self>,
+    ) -> ParseResult<'a, ()> {
+        Self::parse_step(input, Context::root(options), buffer)
+    }
+
+    /// Push the next node (preceded by text, if any) to `buffer`, followed by the blank lines
+    /// separating it from the next sibling.
+    fn parse_step(
+        input: &'a str,
+        context: Context<'_, '_>,
+        buffer: &mut Vec<Self>,
+    ) -> ParseResult<'a, ()> {
+        let preformatted = context.preformatted;
+        let trim_end = |text: &'a str| if preformatted { text } else { text.trim_end() };
+        let options = context.options;
 
+        let rest = match Self::parse_non_text(input, context) {
+            Ok((rest, node)) => {
                 buffer.push(node);
+                rest
+            }
+            Err(error @ nom::Err::Failure(_)) => return Err(error),
+            Err(_) => {
+                let (rest, (text, next)) = Self::parse_text(input, context)?;
+
+                let trimmed = trim_end(text);
+
+                buffer.push(Self::Text(trimmed.into()));
 
                 if let Some(node) = next {
+                    let next_is_inline = node.is_inline_level_with(options);
+
+                    Self::push_separator(buffer, &text[trimmed.len()..], next_is_inline, options);
                     buffer.push(node);
                 }
 
-                remaining = rest.trim_start();
+                rest
+            }
+        };
+
+        let remaining = if preformatted {
+            rest
+        } else {
+            rest.trim_start()
+        };
+
+        // Only between siblings, not before an end tag or eof.
+        if !remaining.is_empty() && !Self::is_end_of_content(remaining, context.parent) {
+            Self::push_separator(
+                buffer,
+                &rest[..rest.len() - remaining.len()],
+                Self::starts_inline_level(remaining, options),
+                options,
+            );
+        }
+
+        Ok((remaining, ()))
+    }
+
+    /// An end tag, or a start tag implying the end tag of `parent`.
+    fn is_end_of_content(input: &str, parent: Option<&str>) -> bool {
+        Element::parse_end_tag(input).is_ok()
+            || parent.is_some_and(|parent| {
+                has_optional_end_tag(parent)
+                    && Element::peek_start_tag_name(input)
+                        .is_some_and(|start| is_closed_by_start_tag(parent, start))
+            })
+    }
+
+    /// Text or an inline element.
+    pub(crate) fn is_inline_level(&self) -> bool {
+        match self {
+            Self::Text(_) => true,
+            Self::Element(Element::Normal { name, .. } | Element::Void { name, .. }) => {
+                is_inline_element(name)
             }
+            _ => false,
+        }
+    }
+
+    /// See [`Node::is_inline_level`], also considering [`ParseOptions::extra_inline_elements`] and
+    /// [`ParseOptions::strict_whitespace`] (any element is then inline).
+    fn is_inline_level_with(&self, options: &ParseOptions<'_>) -> bool {
+        match self {
+            Self::Element(element) => {
+                options.strict_whitespace || options.is_inline_element(element.name())
+            }
+            node => node.is_inline_level(),
+        }
+    }
+
+    /// Whether the next node in `input` is text or an inline element (see
+    /// [`Node::is_inline_level_with`]), without parsing it.
+    fn starts_inline_level(input: &str, options: &ParseOptions<'_>) -> bool {
+        match Element::peek_start_tag_name(input) {
+            Some(name) => options.strict_whitespace || options.is_inline_element(name),
+            None => {
+                !input.starts_with("<!")
+                    && !input.starts_with("<?")
+                    && BogusComment::parse(input).is_err()
+            }
+        }
+    }
+
+    /// Blank lines, or whitespace if significant (i.e. between inline-level siblings).
+    fn push_separator(
+        buffer: &mut Vec<Self>,
+        whitespace: &str,
+        next_is_inline: bool,
+        options: &ParseOptions<'_>,
+    ) {
+        let count = whitespace.matches('\n').count().saturating_sub(1);
+
+        if count > 0 {
+            buffer.push(Self::BlankLines(count));
+        } else if !whitespace.is_empty()
+            && next_is_inline
+            && buffer
+                .last()
+                .is_some_and(|previous| previous.is_inline_level_with(options))
+        {
+            buffer.push(Self::Whitespace);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        comment::Comment,
+        element::{
+            attribute::{Attribute, AttributeValue, Quote},
+            Element,
+        },
+        to_html, Fragment, Node,
+    };
+    use crate::parse::{ParseErrorKind, ParseOptions};
+
+    #[test]
+    fn test_canonicalize() {
+        let parse = |input| {
+            let (_, nodes) = Node::parse_many(input).unwrap();
+
+            assert_eq!(nodes.len(), 1);
+            nodes.into_iter().next().unwrap()
+        };
+
+        let a = parse("<DIV id=a class='b'>\n\n  Hello\n  world\n\n<pre> x  y </pre></DIV>");
+        let b = parse(r#"<div class="b" id="a">Hello world<pre> x  y </pre></div>"#);
+
+        assert_ne!(a, b);
+        assert_eq!(a.canonicalize(), b.canonicalize());
+        assert_eq!(HashSet::from([a.canonicalize(), b.canonicalize()]).len(), 1);
+
+        // Preformatted whitespace and component names are significant.
+        assert_ne!(
+            parse("<pre>a  b</pre>").canonicalize(),
+            parse("<pre>a b</pre>").canonicalize()
+        );
+        assert_ne!(
+            parse("<MyComponent></MyComponent>").canonicalize(),
+            parse("<mycomponent></mycomponent>").canonicalize()
+        );
+        assert_eq!(
+            parse(r#"<input required type="text">"#).canonicalize(),
+            Node::Element(Element::Void {
+                name: "input".into(),
+                attributes: vec![
+                    Attribute {
+                        name: "required".into(),
+                        value: None,
+                    },
+                    Attribute {
+                        name: "type".into(),
+                        value: Some(AttributeValue {
+                            text: "text".into(),
+                            quote: Quote::Double,
+                        }),
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_blank_lines() {
+        assert_eq!(
+            Node::parse_many("<br>\n\n\n<br>\n<br>\n\n"),
+            Ok((
+                "",
+                vec![
+                    Node::Element(Element::Void {
+                        name: "br".into(),
+                        attributes: vec![]
+                    }),
+                    Node::BlankLines(2),
+                    Node::Element(Element::Void {
+                        name: "br".into(),
+                        attributes: vec![]
+                    }),
+                    Node::Element(Element::Void {
+                        name: "br".into(),
+                        attributes: vec![]
+                    }),
+                ]
+            ))
+        );
+
+        assert_eq!(
+            Node::parse_many("<p>\n\n  Hello\n\n  <br>\n\n</p>"),
+            Ok((
+                "",
+                vec![Node::Element(Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Text("Hello".into()),
+                        Node::BlankLines(1),
+                        Node::Element(Element::Void {
+                            name: "br".into(),
+                            attributes: vec![]
+                        }),
+                    ]
+                })]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_whitespace() {
+        let b = || {
+            Node::Element(Element::Normal {
+                name: "b".into(),
+                attributes: vec![],
+                content: vec![Node::Text("b".into())],
+            })
+        };
+
+        assert_eq!(
+            Node::parse_many("a <b>b</b> <b>b</b>\n<div></div> <b>b</b> c"),
+            Ok((
+                "",
+                vec![
+                    Node::Text("a".into()),
+                    Node::Whitespace,
+                    b(),
+                    Node::Whitespace,
+                    b(),
+                    Node::Element(Element::Normal {
+                        name: "div".into(),
+                        attributes: vec![],
+                        content: vec![]
+                    }),
+                    b(),
+                    Node::Whitespace,
+                    Node::Text("c".into()),
+                ]
+            ))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() -> anyhow::Result<()> {
+        let input = concat!(
+            "<!DOCTYPE html>\n",
+            "<!-- comment -->\n",
+            "<div id='app' hidden>\n",
+            "  <p>Hello <b>world</b></p>\n",
+            "  <br>\n",
+            "</div>\n",
+        );
+
+        let (_, nodes) = Node::parse_many(input)?;
+
+        let json = serde_json::to_string(&nodes)?;
+
+        assert_eq!(serde_json::from_str::<Vec<Node>>(&json)?, nodes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_text_full_of_lt() {
+        let text = "<".repeat(100_000) + &"< <!-- <![CDATA[ <a<".repeat(10_000);
+        let input = format!("<p>{text}</p>");
+
+        assert_eq!(
+            Node::parse_many(&input),
+            Ok((
+                "",
+                vec![Node::Element(Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text(text.as_str().into())],
+                })]
+            ))
+        );
+
+        // Bogus comments without a `>`.
+        let text = "<".repeat(100_000) + &"< </ <? <! <a<".repeat(10_000);
+
+        assert_eq!(
+            Node::parse_many(&text),
+            Ok(("", vec![Node::Text(text.as_str().into())]))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_tag_name() {
+        // A tag name must start with an ASCII letter, otherwise `<` is text.
+        for input in [
+            "<>",
+            "< div>",
+            "a <> b",
+            "a < div> b",
+            "<1a>",
+            "<\u{e9}>",
+            "a<",
+        ] {
+            assert_eq!(
+                Node::parse_many(input),
+                Ok(("", vec![Node::Text(input.into())])),
+                "{input}"
+            );
         }
+
+        assert_eq!(
+            Node::parse_many("<div><>a</div>"),
+            Ok((
+                "",
+                vec![Node::Element(Element::Normal {
+                    name: "div".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("<>a".into())],
+                })]
+            ))
+        );
+
+        for name in ["MyComponent", "my-component", "Foo.Bar", "x:y", "a1"] {
+            let input = format!("<{name}></{name}>");
+
+            assert_eq!(
+                Node::parse_many(&input),
+                Ok((
+                    "",
+                    vec![Node::Element(Element::Normal {
+                        name: name.into(),
+                        attributes: vec![],
+                        content: vec![],
+                    })]
+                )),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_one() {
+        let p = || {
+            Node::Element(Element::Normal {
+                name: "p".into(),
+                attributes: vec![],
+                content: vec![Node::Text("a".into())],
+            })
+        };
+
+        assert_eq!(Node::parse_one("  <p>a</p> <b>"), Ok((p(), " <b>")));
+        assert_eq!(
+            Node::parse_one("a b <p>"),
+            Ok((Node::Text("a b".into()), " <p>"))
+        );
+        assert_eq!(
+            Node::parse_one("<!-- a -->b"),
+            Ok((Node::Comment(Comment(" a ".into())), "b"))
+        );
+
+        assert_eq!(
+            Node::parse_one(" \n").unwrap_err().kind(),
+            &ParseErrorKind::UnexpectedEof
+        );
+        assert_eq!(Node::parse_one("").unwrap_err().offset(), 0);
+
+        let error = Node::parse_one(" </p><p>").unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEndTag);
+        assert_eq!(error.offset(), 1);
+    }
+
+    #[test]
+    fn test_parse_fragment() {
+        let element = |name: &'static str, text: &'static str| {
+            Node::Element(Element::Normal {
+                name: name.into(),
+                attributes: vec![],
+                content: vec![Node::Text(text.into())],
+            })
+        };
+
+        let input = String::from("<p>a</p> <b>b</b>");
+        let fragment = input.parse::<Fragment>();
+        drop(input);
+
+        assert_eq!(
+            fragment,
+            Ok(Fragment(vec![element("p", "a"), element("b", "b")]))
+        );
+        assert_eq!(fragment.unwrap().to_string(), "<p>a</p><b>b</b>");
+
+        assert_eq!("".parse::<Fragment>(), Ok(Fragment::default()));
+
+        let error = "<p>a</p></b>".parse::<Fragment>().unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEndTag);
+        assert_eq!(error.offset(), 8);
+    }
+
+    #[test]
+    fn test_find_all() {
+        let (_, nodes) = Node::parse_many(concat!(
+            "<ul>\n",
+            "  <li>a\n",
+            "    <ol><li>b<li>c</ol>\n",
+            "  <LI>d</LI>\n",
+            "</ul>\n",
+        ))
+        .unwrap();
+
+        assert_eq!(nodes[0].find_all("li").len(), 4);
+        assert_eq!(nodes[0].find_all("ol").len(), 1);
+        assert_eq!(nodes[0].find_all("ul").len(), 1);
+        assert!(Node::Text("li".into()).find_all("li").is_empty());
+    }
+
+    #[test]
+    fn test_replace_elements() {
+        let (_, mut nodes) = Node::parse_many(concat!(
+            r#"<div style="a"><a href="/">a</a>"#,
+            r#"<p><A>b</A><span style="b">c</span></p>"#,
+            r#"<a class="d">d</a></div>"#,
+        ))
+        .unwrap();
+
+        nodes[0].replace_elements("a", |element| {
+            if element.has_attr("class") {
+                return;
+            }
+
+            if let Element::Normal { attributes, .. } = element {
+                attributes.push(Attribute {
+                    name: "class".into(),
+                    value: Some(AttributeValue {
+                        text: "link".into(),
+                        quote: Quote::Double,
+                    }),
+                });
+            }
+        });
+
+        nodes[0].replace_elements("span", |element| {
+            if let Element::Normal { attributes, .. } = element {
+                attributes.retain(|attribute| attribute.name != "style");
+            }
+        });
+
+        assert_eq!(
+            to_html(&nodes),
+            concat!(
+                r#"<div style="a"><a href="/" class="link">a</a>"#,
+                r#"<p><A class="link">b</A><span>c</span></p>"#,
+                r#"<a class="d">d</a></div>"#,
+            )
+        );
+
+        let mut text = Node::Text("a".into());
+
+        text.replace_elements("a", |_| unreachable!());
+
+        assert_eq!(text, Node::Text("a".into()));
+    }
+
+    #[test]
+    fn test_text_content() {
+        let (_, nodes) = Node::parse_many(concat!(
+            "<article>\n",
+            "  <h1>Title</h1><p>Some <b>bold</b>, <i>it</i><em>alic</em>\n",
+            "    text.</p>\n",
+            "  <!-- comment -->\n",
+            "  <section><h2>Sub</h2>\n",
+            "\n",
+            "    <p>A &amp; B<br>C</p><script>let x;</script><style>p {}</style>\n",
+            "  </section>\n",
+            "</article>\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            nodes[0].text_content(),
+            "Title Some bold, italic text. Sub A &amp; B C"
+        );
+        assert_eq!(Node::Text("  a \n b ".into()).text_content(), "a b");
+        assert_eq!(Node::Comment(Comment("a".into())).text_content(), "");
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let nodes: Vec<Node<'static>> = {
+            let input = String::from(concat!(
+                "<!-- a --><? b ?><![CDATA[ c ]]><! d>",
+                "<div id=e hidden><p>f <b>g</b></p><br></div>",
+            ));
+
+            let (_, nodes) = Node::parse_many(&input).unwrap();
+
+            nodes.into_iter().map(Node::into_owned).collect()
+        };
+
+        assert_eq!(
+            to_html(&nodes),
+            "<!-- a --><? b ?><![CDATA[ c ]]><! d><div id=e hidden><p>f <b>g</b></p><br></div>"
+        );
+        assert_eq!(nodes.clone(), nodes);
+    }
+
+    #[test]
+    fn test_to_html() {
+        let input = concat!(
+            "<!DOCTYPE html>\n",
+            "<!-- comment -->\n",
+            "<!--\n  multiline\n  comment\n-->\n",
+            "<!--[if IE]><p>IE</p><![endif]-->\n\n\n",
+            "<div id=\"a\" class='b' data-c=d hidden>\n",
+            "  <p>Hello <b>world</b></p>\n",
+            "  <br>\n",
+            "  <my-element/>\n",
+            "  <pre>  keep\n  me</pre>\n",
+            "  <script>if (a < b) {}</script>\n",
+            "</div>\n",
+        );
+
+        let (_, nodes) = Node::parse_many(input).unwrap();
+        let html = to_html(&nodes);
+
+        assert_eq!(Node::parse_many(&html), Ok(("", nodes)));
+        assert!(html.contains("<div id=\"a\" class='b' data-c=d hidden><p>Hello <b>world</b></p>"));
+        assert!(html.contains("<br><my-element/>"));
+    }
+
+    #[test]
+    fn test_to_html_escaping() {
+        let node = Node::Element(Element::Normal {
+            name: "p".into(),
+            attributes: vec![
+                Attribute {
+                    name: "title".into(),
+                    value: Some(AttributeValue {
+                        text: r#"a "b" 'c' & <d>"#.into(),
+                        quote: Quote::Double,
+                    }),
+                },
+                Attribute {
+                    name: "class".into(),
+                    value: Some(AttributeValue {
+                        text: "e f".into(),
+                        quote: Quote::Unquoted,
+                    }),
+                },
+            ],
+            content: vec![Node::Text("1 < 2 && 3 &gt; 2".into())],
+        });
+
+        assert_eq!(
+            node.to_string(),
+            r#"<p title="a &quot;b&quot; 'c' &amp; &lt;d>" class="e f">1 &lt; 2 &amp;&amp; 3 &gt; 2</p>"#
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let input = concat!(
+            "<!DOCTYPE html>\n",
+            "<div>\n",
+            "  <!-- a <!-- comment -->\n",
+            "  <ul>\n",
+            "    <li>one\n",
+            "    <li class=\"b\">two <b>and a half</b></li>\n",
+            "  </ul>\n",
+            "  <br>\n",
+            "  <![CDATA[ x ]]><?php echo 1; ?>\n",
+            "</div>\n",
+        );
+
+        let (_, nodes) = Node::parse_many(input).unwrap();
+
+        let spans = |nodes: &[Node]| {
+            nodes
+                .iter()
+                .map(|node| node.span(input).map(|span| &input[span]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            spans(&nodes),
+            [None, Some(&input[16..])].map(|span| span.map(str::trim_end))
+        );
+
+        let Node::Element(Element::Normal { content, .. }) = &nodes[1] else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            spans(content),
+            [
+                Some("<!-- a <!-- comment -->"),
+                Some("<ul>\n    <li>one\n    <li class=\"b\">two <b>and a half</b></li>\n  </ul>"),
+                Some("<br>"),
+                Some("<![CDATA[ x ]]>"),
+                Some("<?php echo 1; ?>"),
+            ]
+        );
+
+        let Node::Element(Element::Normal { content, .. }) = &content[1] else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            spans(content),
+            [
+                Some("<li>one"),
+                Some("<li class=\"b\">two <b>and a half</b></li>")
+            ]
+        );
+
+        let Node::Element(Element::Normal { content, .. }) = &content[1] else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            spans(content),
+            [Some("two"), None, Some("<b>and a half</b>")]
+        );
+
+        assert_eq!(Node::Text("owned".to_string().into()).span(input), None);
+        assert_eq!(Node::Text("elsewhere".into()).span(input), None);
+    }
+
+    #[test]
+    fn test_span_in_context() {
+        let spans = |input, options: &ParseOptions| {
+            let (_, nodes) = Node::parse_many_with_options(input, options).unwrap();
+
+            let Node::Element(element) = &nodes[0] else {
+                unreachable!();
+            };
+
+            let mut spans = vec![nodes[0].span(input).map(|span| &input[span])];
+
+            spans.extend(
+                element
+                    .content()
+                    .iter()
+                    .map(|node| node.span(input).map(|span| &input[span])),
+            );
+
+            spans
+        };
+
+        assert_eq!(
+            spans(
+                "<svg>\n  <rect x=\"1\"/>\n  <g><circle/></g>\n</svg>",
+                &ParseOptions::default()
+            ),
+            [
+                Some("<svg>\n  <rect x=\"1\"/>\n  <g><circle/></g>\n</svg>"),
+                Some("<rect x=\"1\"/>"),
+                Some("<g><circle/></g>"),
+            ]
+        );
+
+        assert_eq!(
+            spans(
+                "<div><section/>\n<p>after</p>\n</div>",
+                &ParseOptions {
+                    self_closing_tags: true,
+                    ..ParseOptions::default()
+                }
+            ),
+            [
+                Some("<div><section/>\n<p>after</p>\n</div>"),
+                Some("<section/>"),
+                Some("<p>after</p>"),
+            ]
+        );
+
+        assert_eq!(
+            spans(
+                "<div><raw><b></RAW> <x-void> a</div>",
+                &ParseOptions {
+                    extra_void_elements: &["x-void"],
+                    extra_raw_text_elements: &["raw"],
+                    ..ParseOptions::default()
+                }
+            ),
+            [
+                Some("<div><raw><b></RAW> <x-void> a</div>"),
+                Some("<raw><b></RAW>"),
+                Some("<x-void>"),
+                Some("a"),
+            ]
+        );
     }
 }
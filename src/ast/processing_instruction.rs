@@ -0,0 +1,66 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use nom::{
+    bytes::complete::{tag, take_until},
+    sequence::delimited,
+    Parser,
+};
+
+use super::error::ParseResult;
+
+/// An XML declaration (`<?xml version="1.0"?>`) or processing instruction (e.g. `<?php ... ?>`).
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessingInstruction<'a>(
+    #[cfg_attr(feature = "serde", serde(borrow))] pub Cow<'a, str>,
+);
+
+impl<'a> ProcessingInstruction<'a> {
+    pub fn parse(input: &str) -> ParseResult<'_, ProcessingInstruction<'_>> {
+        delimited(tag("<?"), take_until("?>"), tag("?>"))
+            .map(|processing_instruction: &str| {
+                ProcessingInstruction(processing_instruction.into())
+            })
+            .parse(input)
+    }
+
+    /// See [`Node::into_owned`](super::Node::into_owned).
+    pub fn into_owned(self) -> ProcessingInstruction<'static> {
+        ProcessingInstruction(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl fmt::Display for ProcessingInstruction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<?{}?>", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProcessingInstruction;
+
+    #[test]
+    fn test_parse_xml_declaration() {
+        assert_eq!(
+            ProcessingInstruction::parse(r#"<?xml version="1.0" encoding="UTF-8"?>"#),
+            Ok((
+                "",
+                ProcessingInstruction(r#"xml version="1.0" encoding="UTF-8""#.into())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_processing_instruction() {
+        assert_eq!(
+            ProcessingInstruction::parse("<?php echo $x; ?>!"),
+            Ok(("!", ProcessingInstruction("php echo $x; ".into())))
+        );
+
+        assert!(ProcessingInstruction::parse("<!DOCTYPE html>").is_err());
+        assert!(ProcessingInstruction::parse("<!--[if IE]><![endif]-->").is_err());
+        assert!(ProcessingInstruction::parse("<?php unterminated").is_err());
+    }
+}
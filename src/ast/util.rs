@@ -1,3 +1,10 @@
 pub fn is_ascii_whitespace(char: char) -> bool {
     char.is_ascii_whitespace()
 }
+
+/// Offset of `slice` in `input`, if it is a subslice of it.
+pub fn offset_in(input: &str, slice: &str) -> Option<usize> {
+    let offset = (slice.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+
+    (offset + slice.len() <= input.len()).then_some(offset)
+}
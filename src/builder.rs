@@ -0,0 +1,196 @@
+//! Construction of nodes in code, e.g. for templating.
+//!
+//! Strings may be borrowed or owned, nodes built from owned (or `'static`) strings only are `'static`.
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::ast::{
+    comment::Comment,
+    element::{
+        attribute::{Attribute, AttributeValue, Quote},
+        is_void_element, Element,
+    },
+    Node,
+};
+
+/// Start building an element, see [`ElementBuilder`].
+pub fn element<'a>(name: impl Into<Cow<'a, str>>) -> ElementBuilder<'a> {
+    ElementBuilder {
+        name: name.into(),
+        attributes: Vec::new(),
+        content: Vec::new(),
+        self_closing: false,
+    }
+}
+
+/// A text node, escaped when emitted.
+pub fn text<'a>(text: impl Into<Cow<'a, str>>) -> Node<'a> {
+    Node::Text(text.into())
+}
+
+pub fn comment<'a>(content: impl Into<Cow<'a, str>>) -> Node<'a> {
+    Node::Comment(Comment(content.into()))
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementBuilder<'a> {
+    name: Cow<'a, str>,
+    attributes: Vec<Attribute<'a>>,
+    content: Vec<Node<'a>>,
+    self_closing: bool,
+}
+
+impl<'a> ElementBuilder<'a> {
+    /// Append an attribute, its value is escaped when emitted.
+    #[must_use]
+    pub fn attr(mut self, name: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        self.attributes.push(Attribute {
+            name: name.into(),
+            value: Some(AttributeValue {
+                text: value.into(),
+                quote: Quote::Double,
+            }),
+        });
+
+        self
+    }
+
+    /// Append an attribute without value (e.g. `hidden`).
+    #[must_use]
+    pub fn flag(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+        self.attributes.push(Attribute {
+            name: name.into(),
+            value: None,
+        });
+
+        self
+    }
+
+    #[must_use]
+    pub fn child(mut self, node: impl Into<Node<'a>>) -> Self {
+        self.content.push(node.into());
+        self
+    }
+
+    #[must_use]
+    pub fn text(self, text: impl Into<Cow<'a, str>>) -> Self {
+        self.child(Node::Text(text.into()))
+    }
+
+    /// Build a self-closed element (e.g. `<my-component/>`), any content is dropped.
+    ///
+    /// Spec void elements (e.g. `br`) are always built as such.
+    #[must_use]
+    pub fn self_closing(mut self) -> Self {
+        self.self_closing = true;
+        self
+    }
+
+    pub fn build(self) -> Node<'a> {
+        Node::Element(self.build_element())
+    }
+
+    pub fn build_element(self) -> Element<'a> {
+        if self.self_closing || is_void_element(&self.name) {
+            Element::Void {
+                name: self.name,
+                attributes: self.attributes,
+            }
+        } else {
+            Element::Normal {
+                name: self.name,
+                attributes: self.attributes,
+                content: self.content,
+            }
+        }
+    }
+}
+
+impl<'a> From<ElementBuilder<'a>> for Node<'a> {
+    fn from(builder: ElementBuilder<'a>) -> Self {
+        builder.build()
+    }
+}
+
+impl<'a> From<ElementBuilder<'a>> for Element<'a> {
+    fn from(builder: ElementBuilder<'a>) -> Self {
+        builder.build_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::Node, parse, to_html};
+    #[cfg(feature = "std")]
+    use crate::{format, Configuration};
+
+    use super::{comment, element, text};
+
+    fn build() -> Vec<Node<'static>> {
+        let items = ["one", "two"].into_iter().enumerate().fold(
+            element("ul").attr("id", "list"),
+            |list, (index, item)| {
+                list.child(
+                    element("li")
+                        .attr("data-index", index.to_string())
+                        .text(item),
+                )
+            },
+        );
+
+        vec![
+            comment("generated"),
+            element("div")
+                .attr("class", "card")
+                .flag("hidden")
+                .child(element("h1").text(String::from("Tom & Jerry")))
+                .child(element("img").attr("src", "a.png").text("dropped"))
+                .child(element("my-icon").self_closing())
+                .child(items)
+                .build(),
+            text("a < b"),
+        ]
+    }
+
+    #[test]
+    fn test_builder_to_html() {
+        let nodes = build();
+        let html = to_html(&nodes);
+
+        assert_eq!(
+            html,
+            concat!(
+                "<!--generated-->",
+                r#"<div class="card" hidden><h1>Tom &amp; Jerry</h1><img src="a.png"><my-icon/>"#,
+                r#"<ul id="list"><li data-index="0">one</li><li data-index="1">two</li></ul>"#,
+                "</div>",
+                "a &lt; b",
+            )
+        );
+
+        assert_eq!(parse(&html).unwrap().len(), nodes.len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_builder_format() -> anyhow::Result<()> {
+        assert_eq!(
+            format(&to_html(&build()), &Configuration::default())?,
+            concat!(
+                "<!-- generated -->\n",
+                "<div class=\"card\" hidden>\n",
+                "  <h1>Tom &amp; Jerry</h1>\n",
+                "  <img src=\"a.png\"/>\n",
+                "  <my-icon/>\n",
+                "  <ul id=\"list\">\n",
+                "    <li data-index=\"0\">one</li>\n",
+                "    <li data-index=\"1\">two</li>\n",
+                "  </ul>\n",
+                "</div>\n",
+                "a &lt; b\n",
+            )
+        );
+
+        Ok(())
+    }
+}
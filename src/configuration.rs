@@ -1,9 +1,428 @@
-#[derive(Debug, Clone, Copy)]
+use std::{borrow::Cow, collections::BTreeMap, fmt};
+
+use crate::parse::ParseOptions;
+
+/// Formatting options, preferably built with [`Configuration::builder`] which validates them.
+///
+/// It isn't `Copy` (unlike previous versions) since its [`element_overrides`] own their map, so
+/// that they can be built at runtime and deserialized: clone it to reuse it, e.g. with
+/// `..configuration.clone()`.
+///
+/// [`element_overrides`]: Configuration::element_overrides
+///
+/// ```
+/// let configuration = hast::Configuration::builder()
+///     .line_width(100)
+///     .indent_width(4)
+///     .build()?;
+/// # Ok::<(), hast::ConfigurationError>(())
+/// ```
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
 pub struct Configuration {
     pub line_width: u32,
     pub indent_width: u8,
+    pub indent_style: IndentStyle,
+    pub line_ending: LineEnding,
+    pub quote_style: QuoteStyle,
+    /// Quote the values that were unquoted in the source (e.g. `value=1`), otherwise they stay
+    /// unquoted unless they must be quoted.
+    pub quote_unquotable_values: bool,
+    pub void_self_close: VoidSelfClose,
+    /// Put a space before the `/` of self-closing tags (e.g. `<br />`, for XHTML served as HTML),
+    /// unless it is on its own line after attributes that don't fit.
+    pub space_before_self_closing_slash: bool,
+    pub tag_case: TagCase,
+    pub attribute_case: AttributeCase,
+    pub attribute_order: AttributeOrder,
+    /// Group attributes by kind, in this order (unlisted kinds go last), keeping the order of
+    /// [`attribute_order`](Self::attribute_order) within each group.
+    pub attribute_grouping: Option<[AttributeGroup; 4]>,
+    pub boolean_attribute_style: BooleanAttributeStyle,
+    pub multiline_attribute_values: MultilineAttributeValues,
+    /// Sort and dedupe the tokens of `class` attributes (unless templated, e.g. `{{ a }}`).
+    pub sort_class_tokens: bool,
+    /// Rewrite character references to `&`, `<`, `>`, `"` and the no-break space in their named,
+    /// lowercase form (e.g. `&#38;` or `&AMP;` as `&amp;`), in text and attribute values. Raw text
+    /// (e.g. in `<script>`) is kept as-is regardless.
+    pub normalize_entities: bool,
+    pub duplicate_attributes: DuplicateAttributes,
+    /// Fail on input that can't be parsed past some point, i.e. from a stray end tag at the top
+    /// level (e.g. `</div>` without a matching `<div>`) on. Otherwise that part is kept verbatim,
+    /// on its own lines after the formatted nodes.
+    pub strict: bool,
+    /// Keep the whitespace around the content of single line comments (e.g. `<!--a  -->`),
+    /// instead of normalizing it to a single space (`<!-- a -->`).
+    pub preserve_comment_whitespace: bool,
+    /// Wrap (at whitespace) the content of single line comments that don't fit within
+    /// `line_width`, once on its own lines. Important (e.g. `<!--! License -->`) and conditional
+    /// comments are never wrapped, nor comments with preserved whitespace.
+    pub reflow_comments: bool,
+    pub remove_comments: RemoveComments,
+    /// Maximum number of consecutive blank lines kept between sibling nodes.
+    pub max_blank_lines: u8,
+    /// End non-empty output with exactly one newline, or with none.
+    pub insert_final_newline: bool,
+    /// Keep the byte order mark (`U+FEFF`) starting the input, if any. It is removed otherwise.
+    pub preserve_bom: bool,
+    /// Keep (as a single space) the whitespace at the start / end of the input if it is
+    /// significant, i.e. next to text or an inline element (e.g. ` <b>partial</b> `). Useful when
+    /// formatting fragments meant to be concatenated, along with `insert_final_newline: false`.
+    pub preserve_document_edges: bool,
+    /// Keep the end tag of empty elements right after the start tag (e.g. `<div></div>`), even
+    /// when the element doesn't fit on one line. Otherwise it goes on its own line in that case.
+    pub collapse_empty_elements: bool,
+    /// Put each attribute on its own line for elements with more attributes than this, even if
+    /// they would fit.
+    pub max_attributes_inline: Option<u8>,
+    /// Put each attribute on its own line for elements with more than one attribute whose
+    /// content always goes on its own lines (e.g. block-level children), even if they would fit.
+    pub break_attributes_with_content: bool,
+    /// Re-indent the CSS of `<style>` elements based on its braces, relative to the element.
+    /// Otherwise it is kept verbatim.
+    pub format_embedded_css: bool,
+    /// Shift the lines of `<script>` elements to the element's indentation, keeping their
+    /// relative indentation. Otherwise they are kept verbatim.
+    pub reindent_embedded_script: bool,
+    pub interpolation: Interpolation,
+    pub text_wrap: TextWrap,
+    pub whitespace_sensitivity: WhitespaceSensitivity,
+    /// Options of the parser, except for
+    /// [`strict_whitespace`](ParseOptions::strict_whitespace) which is set from
+    /// [`whitespace_sensitivity`](Self::whitespace_sensitivity).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parse_options: ParseOptions<'static>,
+    /// How some elements are formatted, by name, instead of the defaults for their kind.
+    pub element_overrides: ElementOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum IndentStyle {
+    Spaces,
+    /// One tab per indentation level, counted as `indent_width` columns when wrapping.
+    Tabs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// Use the line ending found the most in the input.
+    Auto,
+}
+
+/// Quotes used around attribute values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum QuoteStyle {
+    /// Use double quotes, unless the value contains one.
+    Double,
+    /// Use single quotes, unless the value contains one.
+    Single,
+    /// Use the quotes found in the source, unquoted values get double quotes.
+    Preserve,
+}
+
+/// How void elements (e.g. `<br>`) are closed.
+///
+/// Elements self-closed by the author (e.g. `<MyComponent/>`) always keep their slash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum VoidSelfClose {
+    /// `<br/>`
+    Slash,
+    /// `<br>`
+    NoSlash,
+}
+
+/// Case of tag names.
+///
+/// Mixed-case (e.g. `<MyComponent>`) and namespaced (e.g. `<svg:rect>`) names are never changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TagCase {
+    Preserve,
+    Lower,
+    Upper,
+}
+
+/// Case of attribute names.
+///
+/// Framework directives (e.g. `:class`, `@click`, `v-bind:fooBar`) and namespaced attributes (e.g.
+/// `xlink:href`) are never changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AttributeCase {
+    Preserve,
+    Lower,
+}
+
+/// Order of attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AttributeOrder {
+    Preserve,
+    /// Sorted case-insensitively (and stably), framework directives (e.g. `:class`, `@click`)
+    /// after the other attributes.
+    Alphabetical,
+}
+
+/// Kind of attribute, see [`Configuration::attribute_grouping`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AttributeGroup {
+    /// e.g. `v-if`, `x-data` or `#default`
+    Directive,
+    /// e.g. `:class`, `v-bind:class` or `x-bind:class`
+    Binding,
+    /// e.g. `@click`, `v-on:click` or `x-on:click`
+    Event,
+    /// Any other attribute, e.g. `class`.
+    Plain,
+}
+
+/// How attributes without a value (e.g. `required`) are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum BooleanAttributeStyle {
+    /// `required`
+    Minimal,
+    /// `required=""`
+    Empty,
+    /// `required="required"`
+    Explicit,
+}
+
+/// How attribute values spanning multiple lines (e.g. a long `style`) are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum MultilineAttributeValues {
+    /// Kept verbatim, i.e. the following lines keep their indentation from the source.
+    Preserve,
+    /// Whitespace containing a newline becomes a single space.
+    Collapse,
+    /// The following lines are indented one level deeper than the attribute, keeping their
+    /// relative indentation. A last line with only whitespace (i.e. before the closing quote)
+    /// is at the attribute's indentation.
+    Indent,
+}
+
+/// Which whitespace between sibling nodes is significant, i.e. kept (as a single space or line
+/// break) and never added, mirroring Prettier's `htmlWhitespaceSensitivity`.
+///
+/// Whitespace at the start / end of an element's content is never significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum WhitespaceSensitivity {
+    /// Between text and [inline elements](crate::ast::element::is_inline_element) (e.g.
+    /// `<span>`), as per their default CSS `display`.
+    Css,
+    /// Between any text and elements, e.g. also between two `<div>`s.
+    Strict,
+    /// None, lines may also be broken between text and inline elements.
+    Ignore,
+}
+
+/// Template interpolations, never broken across lines when reflowing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Interpolation {
+    /// Text is broken on any whitespace.
+    None,
+    /// `{{ … }}` (e.g. Vue, Angular, Handlebars, Jinja).
+    Mustache,
+    /// `{{ … }}` as well as `${ … }` (e.g. JavaScript template literals).
+    MustacheAndDollar,
+}
+
+/// How text is broken into lines, whitespace is collapsed either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TextWrap {
+    /// Fill lines up to `line_width`, breaking on any whitespace.
+    Reflow,
+    /// Break lines where the source does, only there.
+    Preserve,
+    /// Never break text.
+    NoWrap,
+}
+
+/// What to do with attributes repeated (case-insensitively) on the same element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum DuplicateAttributes {
+    /// Fail with [`crate::ParseErrorKind::DuplicateAttribute`].
+    Error,
+    /// Keep the first occurrence, as browsers do.
+    KeepFirst,
+}
+
+/// Which comments are kept when formatting (e.g. for production output).
+///
+/// Conditional comments (e.g. `<!--[if IE]>`) and ignore comments (see
+/// [`Comment::is_ignore`](crate::ast::comment::Comment::is_ignore)) are always kept, as well as
+/// the blank lines around removed comments unless redundant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum RemoveComments {
+    Keep,
+    /// Remove comments, as well as bogus ones (e.g. `<! foo>`).
+    RemoveAll,
+    /// Only keep comments starting with `!` (e.g. `<!--! MIT License -->`), used for legal notices.
+    KeepImportant,
+}
+
+/// See [`Configuration::element_overrides`], tag names are compared case-insensitively (e.g.
+/// `<TABLE>` is overridden along with `<table>`).
+///
+/// ```
+/// use hast::{ElementDisplay, ElementOverride, ElementOverrides};
+///
+/// let element_overrides: ElementOverrides = [(
+///     "table",
+///     ElementOverride {
+///         display: Some(ElementDisplay::Block),
+///         ..ElementOverride::default()
+///     },
+/// )]
+/// .into_iter()
+/// .collect();
+///
+/// assert!(element_overrides.get("TABLE").is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "BTreeMap<String, ElementOverride>",
+        into = "BTreeMap<String, ElementOverride>"
+    )
+)]
+pub struct ElementOverrides(BTreeMap<String, ElementOverride>);
+
+impl ElementOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the previous override of `name`, if any.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        element_override: ElementOverride,
+    ) -> Option<ElementOverride> {
+        let mut name = name.into();
+
+        name.make_ascii_lowercase();
+
+        self.0.insert(name, element_override)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ElementOverride> {
+        let name = if name.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            Cow::Owned(name.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(name)
+        };
+
+        self.0.get(name.as_ref())
+    }
+
+    /// Lowercase names and their override, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ElementOverride)> {
+        self.0
+            .iter()
+            .map(|(name, element_override)| (name.as_str(), element_override))
+    }
+}
+
+impl<N: Into<String>> FromIterator<(N, ElementOverride)> for ElementOverrides {
+    fn from_iter<I: IntoIterator<Item = (N, ElementOverride)>>(iter: I) -> Self {
+        let mut element_overrides = Self::new();
+
+        for (name, element_override) in iter {
+            element_overrides.insert(name, element_override);
+        }
+
+        element_overrides
+    }
+}
+
+impl From<BTreeMap<String, ElementOverride>> for ElementOverrides {
+    fn from(map: BTreeMap<String, ElementOverride>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<ElementOverrides> for BTreeMap<String, ElementOverride> {
+    fn from(ElementOverrides(map): ElementOverrides) -> Self {
+        map
+    }
+}
+
+/// See [`Configuration::element_overrides`], `None` keeps the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct ElementOverride {
+    pub display: Option<ElementDisplay>,
+    pub attribute_wrap: Option<AttributeWrap>,
+}
+
+/// How an element is laid out among its siblings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ElementDisplay {
+    /// Like an [inline element](crate::ast::element::is_inline_element) (e.g. `<a>`), kept on the
+    /// same line as adjacent text or inline elements. The whitespace around it is kept as such.
+    Inline,
+    /// Like a block element (e.g. `<div>`), on its own line along with its content.
+    Block,
+    /// Kept as-is in the source, as if preceded by an ignore comment (see
+    /// [`Comment::is_ignore`](crate::ast::comment::Comment::is_ignore)).
+    Preserve,
+}
+
+/// When to put each attribute of an element on its own line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AttributeWrap {
+    /// Only if they don't fit on one line, whatever [`Configuration::max_attributes_inline`] and
+    /// [`Configuration::break_attributes_with_content`].
+    Fit,
+    /// Always.
+    OnePerLine,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MinifyConfig {
+    /// Remove comments, conditional comments are always kept.
+    pub remove_comments: bool,
 }
 
 impl Default for Configuration {
@@ -11,6 +430,361 @@ impl Default for Configuration {
         Self {
             line_width: 80,
             indent_width: 2,
+            indent_style: IndentStyle::Spaces,
+            line_ending: LineEnding::Lf,
+            quote_style: QuoteStyle::Double,
+            quote_unquotable_values: true,
+            void_self_close: VoidSelfClose::Slash,
+            space_before_self_closing_slash: false,
+            tag_case: TagCase::Preserve,
+            attribute_case: AttributeCase::Preserve,
+            attribute_order: AttributeOrder::Preserve,
+            attribute_grouping: None,
+            boolean_attribute_style: BooleanAttributeStyle::Minimal,
+            multiline_attribute_values: MultilineAttributeValues::Preserve,
+            sort_class_tokens: false,
+            normalize_entities: false,
+            duplicate_attributes: DuplicateAttributes::Error,
+            strict: true,
+            preserve_comment_whitespace: false,
+            reflow_comments: false,
+            remove_comments: RemoveComments::Keep,
+            max_blank_lines: 0,
+            insert_final_newline: true,
+            preserve_bom: true,
+            preserve_document_edges: false,
+            collapse_empty_elements: false,
+            max_attributes_inline: None,
+            break_attributes_with_content: false,
+            format_embedded_css: false,
+            reindent_embedded_script: false,
+            interpolation: Interpolation::Mustache,
+            text_wrap: TextWrap::Reflow,
+            whitespace_sensitivity: WhitespaceSensitivity::Css,
+            parse_options: ParseOptions::default(),
+            element_overrides: ElementOverrides::new(),
+        }
+    }
+}
+
+impl Configuration {
+    /// Start from the default configuration, see [`ConfigurationBuilder`].
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder {
+            configuration: Self::default(),
+        }
+    }
+
+    /// Bundled configuration for an ecosystem, by name, otherwise the same as the default:
+    /// - `"xhtml"`: lowercase names, `<br />`, `required="required"`, every value quoted,
+    /// - `"html5"`: lowercase names, `<br>`, `required`,
+    /// - `"vue"`: self-closed components (e.g. `<MyComponent/>`, see
+    ///   [`ParseOptions::self_closing_tags`]), directives before bindings, events and other
+    ///   attributes, `{{ … }}` interpolations,
+    /// - `"prettier"`: mirroring Prettier's defaults as far as possible, e.g. `<br />`.
+    pub fn preset(name: &str) -> Option<Self> {
+        let default = Self::default();
+
+        match name {
+            "xhtml" => Some(Self {
+                quote_style: QuoteStyle::Double,
+                quote_unquotable_values: true,
+                void_self_close: VoidSelfClose::Slash,
+                space_before_self_closing_slash: true,
+                tag_case: TagCase::Lower,
+                attribute_case: AttributeCase::Lower,
+                boolean_attribute_style: BooleanAttributeStyle::Explicit,
+                ..default
+            }),
+            "html5" => Some(Self {
+                void_self_close: VoidSelfClose::NoSlash,
+                tag_case: TagCase::Lower,
+                attribute_case: AttributeCase::Lower,
+                boolean_attribute_style: BooleanAttributeStyle::Minimal,
+                ..default
+            }),
+            "vue" => Some(Self {
+                void_self_close: VoidSelfClose::Slash,
+                attribute_grouping: Some([
+                    AttributeGroup::Directive,
+                    AttributeGroup::Binding,
+                    AttributeGroup::Event,
+                    AttributeGroup::Plain,
+                ]),
+                interpolation: Interpolation::Mustache,
+                parse_options: ParseOptions {
+                    self_closing_tags: true,
+                    ..default.parse_options
+                },
+                ..default
+            }),
+            "prettier" => Some(Self {
+                line_width: 80,
+                indent_width: 2,
+                quote_style: QuoteStyle::Double,
+                void_self_close: VoidSelfClose::Slash,
+                space_before_self_closing_slash: true,
+                max_blank_lines: 1,
+                collapse_empty_elements: true,
+                whitespace_sensitivity: WhitespaceSensitivity::Css,
+                ..default
+            }),
+            _ => None,
+        }
+    }
+
+    /// # Errors
+    /// See [`ConfigurationError`].
+    pub fn validate(&self) -> Result<(), ConfigurationError> {
+        if self.line_width == 0 {
+            return Err(ConfigurationError::ZeroLineWidth);
         }
+
+        if self.indent_width == 0 && self.indent_style == IndentStyle::Tabs {
+            return Err(ConfigurationError::ZeroTabWidth);
+        }
+
+        Ok(())
+    }
+
+    /// See [`Configuration::element_overrides`].
+    pub(crate) fn element_override(&self, name: &str) -> ElementOverride {
+        self.element_overrides
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+macro_rules! setters {
+    ($($field:ident: $type:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("See [`Configuration::", stringify!($field), "`].")]
+            #[must_use]
+            pub fn $field(mut self, $field: $type) -> Self {
+                self.configuration.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// Builder of a validated [`Configuration`], unset options keep their default.
+#[derive(Debug, Clone)]
+pub struct ConfigurationBuilder {
+    configuration: Configuration,
+}
+
+impl ConfigurationBuilder {
+    setters! {
+        line_width: u32,
+        indent_width: u8,
+        indent_style: IndentStyle,
+        line_ending: LineEnding,
+        quote_style: QuoteStyle,
+        quote_unquotable_values: bool,
+        void_self_close: VoidSelfClose,
+        space_before_self_closing_slash: bool,
+        tag_case: TagCase,
+        attribute_case: AttributeCase,
+        attribute_order: AttributeOrder,
+        attribute_grouping: Option<[AttributeGroup; 4]>,
+        boolean_attribute_style: BooleanAttributeStyle,
+        multiline_attribute_values: MultilineAttributeValues,
+        sort_class_tokens: bool,
+        normalize_entities: bool,
+        duplicate_attributes: DuplicateAttributes,
+        strict: bool,
+        preserve_comment_whitespace: bool,
+        reflow_comments: bool,
+        remove_comments: RemoveComments,
+        max_blank_lines: u8,
+        insert_final_newline: bool,
+        preserve_bom: bool,
+        preserve_document_edges: bool,
+        collapse_empty_elements: bool,
+        max_attributes_inline: Option<u8>,
+        break_attributes_with_content: bool,
+        format_embedded_css: bool,
+        reindent_embedded_script: bool,
+        interpolation: Interpolation,
+        text_wrap: TextWrap,
+        whitespace_sensitivity: WhitespaceSensitivity,
+        parse_options: ParseOptions<'static>,
+        element_overrides: ElementOverrides,
+    }
+
+    /// # Errors
+    /// See [`ConfigurationError`].
+    pub fn build(self) -> Result<Configuration, ConfigurationError> {
+        self.configuration.validate()?;
+
+        Ok(self.configuration)
+    }
+}
+
+/// An invalid [`Configuration`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ConfigurationError {
+    /// `line_width` is 0.
+    ZeroLineWidth,
+    /// `indent_width` is 0 while indenting with [`IndentStyle::Tabs`].
+    ZeroTabWidth,
+}
+
+impl fmt::Display for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroLineWidth => f.write_str("line width must be at least 1"),
+            Self::ZeroTabWidth => f.write_str("indent width must be at least 1 with tabs"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigurationError {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use super::AttributeWrap;
+    use super::{
+        Configuration, ConfigurationError, ElementDisplay, ElementOverride, ElementOverrides,
+        IndentStyle, QuoteStyle,
+    };
+
+    #[test]
+    fn test_preset() -> anyhow::Result<()> {
+        let input = "<BR><input DISABLED><MyComponent/>";
+
+        assert_eq!(
+            crate::format(input, &Configuration::default())?,
+            "<BR/>\n<input DISABLED/>\n<MyComponent/>\n"
+        );
+
+        let format_with = |name| {
+            crate::format(
+                input,
+                &Configuration::preset(name).expect("a bundled preset"),
+            )
+        };
+
+        assert_eq!(
+            format_with("xhtml")?,
+            "<br />\n<input disabled=\"disabled\" />\n<MyComponent />\n"
+        );
+        assert_eq!(
+            format_with("html5")?,
+            "<br>\n<input disabled>\n<MyComponent/>\n"
+        );
+        assert_eq!(
+            format_with("prettier")?,
+            "<BR />\n<input DISABLED />\n<MyComponent />\n"
+        );
+
+        assert_eq!(
+            crate::format(
+                r#"<div class="a" @click="b" :id="c" v-if="d"><MyComponent/></div>"#,
+                &Configuration::preset("vue").expect("a bundled preset"),
+            )?,
+            "<div v-if=\"d\" :id=\"c\" @click=\"b\" class=\"a\">\n  <MyComponent/>\n</div>\n"
+        );
+
+        assert!(Configuration::preset("unknown").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder() {
+        let configuration = Configuration::builder()
+            .line_width(100)
+            .indent_width(4)
+            .quote_style(QuoteStyle::Single)
+            .build()
+            .unwrap();
+
+        assert_eq!(configuration.line_width, 100);
+        assert_eq!(configuration.indent_width, 4);
+        assert_eq!(configuration.quote_style, QuoteStyle::Single);
+        assert_eq!(
+            configuration.indent_style,
+            Configuration::default().indent_style
+        );
+
+        assert!(Configuration::builder().indent_width(0).build().is_ok());
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        assert_eq!(
+            Configuration::builder().line_width(0).build().unwrap_err(),
+            ConfigurationError::ZeroLineWidth
+        );
+        assert_eq!(
+            Configuration::builder()
+                .indent_style(IndentStyle::Tabs)
+                .indent_width(0)
+                .build()
+                .unwrap_err(),
+            ConfigurationError::ZeroTabWidth
+        );
+        assert!(crate::format(
+            "<p>Hello</p>",
+            &Configuration {
+                line_width: 0,
+                ..Configuration::default()
+            }
+        )
+        .is_err());
+
+        assert_eq!(
+            ConfigurationError::ZeroLineWidth.to_string(),
+            "line width must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_element_overrides() {
+        let block = ElementOverride {
+            display: Some(ElementDisplay::Block),
+            ..ElementOverride::default()
+        };
+
+        let mut element_overrides: ElementOverrides = [("Table", block)].into_iter().collect();
+
+        assert_eq!(element_overrides.get("table"), Some(&block));
+        assert_eq!(element_overrides.get("TABLE"), Some(&block));
+        assert_eq!(element_overrides.get("tr"), None);
+
+        assert_eq!(
+            element_overrides.insert("TABLE", ElementOverride::default()),
+            Some(block)
+        );
+        assert_eq!(
+            element_overrides.iter().collect::<Vec<_>>(),
+            [("table", &ElementOverride::default())]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_element_overrides() -> anyhow::Result<()> {
+        let configuration: Configuration = serde_json::from_str(
+            r#"{ "elementOverrides": { "A": { "display": "inline", "attributeWrap": "onePerLine" } } }"#,
+        )?;
+
+        assert_eq!(
+            configuration.element_override("a"),
+            ElementOverride {
+                display: Some(ElementDisplay::Inline),
+                attribute_wrap: Some(AttributeWrap::OnePerLine),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&configuration.element_overrides)?,
+            serde_json::json!({ "a": { "display": "inline", "attributeWrap": "onePerLine" } })
+        );
+
+        Ok(())
     }
 }
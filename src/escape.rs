@@ -0,0 +1,198 @@
+use alloc::{borrow::Cow, string::String};
+
+/// Escape `&` (unless it starts a character reference) and `<` in text.
+pub fn escape_text(text: &str) -> Cow<'_, str> {
+    escape(text, None)
+}
+
+/// Escape `&` (unless it starts a character reference), `<` and the delimiting `quote`, if any,
+/// in an attribute value.
+pub fn escape_attribute_value(text: &str, quote: Option<char>) -> Cow<'_, str> {
+    escape(text, quote)
+}
+
+fn escape(text: &str, quote: Option<char>) -> Cow<'_, str> {
+    let needs_escaping = |(index, char): (usize, char)| match char {
+        '&' => !starts_with_character_reference(&text[index..]),
+        '<' => true,
+        char => Some(char) == quote,
+    };
+
+    if !text.char_indices().any(needs_escaping) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut buffer = String::with_capacity(text.len() + 8);
+
+    for (index, char) in text.char_indices() {
+        if !needs_escaping((index, char)) {
+            buffer.push(char);
+            continue;
+        }
+
+        buffer.push_str(match char {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '"' => "&quot;",
+            '\'' => "&#39;",
+            _ => unreachable!(),
+        });
+    }
+
+    Cow::Owned(buffer)
+}
+
+/// Characters referred to by their preferred name, i.e. those escaped above and the no-break
+/// space.
+#[cfg(feature = "std")]
+const NAMED_REFERENCES: &[(&str, u32)] = &[
+    ("amp", 0x26),
+    ("lt", 0x3C),
+    ("gt", 0x3E),
+    ("quot", 0x22),
+    ("nbsp", 0xA0),
+];
+
+/// Rewrite references to [`NAMED_REFERENCES`] in their preferred form, e.g. `&#38;`, `&#x26;`
+/// and `&AMP;` as `&amp;`. Other references are kept as-is.
+#[cfg(feature = "std")]
+pub fn normalize_character_references(text: &str) -> Cow<'_, str> {
+    let mut buffer = String::new();
+    let mut last = 0;
+
+    for (index, _) in text.match_indices('&') {
+        if let Some((length, name)) = preferred_character_reference(&text[index..]) {
+            buffer.push_str(&text[last..index]);
+            buffer.push('&');
+            buffer.push_str(name);
+            buffer.push(';');
+            last = index + length;
+        }
+    }
+
+    if last == 0 {
+        return Cow::Borrowed(text);
+    }
+
+    buffer.push_str(&text[last..]);
+
+    Cow::Owned(buffer)
+}
+
+/// Length and preferred name of the reference starting `text`, if it isn't in that form already.
+#[cfg(feature = "std")]
+fn preferred_character_reference(text: &str) -> Option<(usize, &'static str)> {
+    let reference = &text.strip_prefix('&')?[..text.find(';')? - 1];
+
+    let code_point = if let Some(hex) = reference
+        .strip_prefix("#x")
+        .or_else(|| reference.strip_prefix("#X"))
+    {
+        parse_digits(hex, 16)?
+    } else if let Some(decimal) = reference.strip_prefix('#') {
+        parse_digits(decimal, 10)?
+    } else {
+        // Legacy uppercase names, see
+        // <https://html.spec.whatwg.org/multipage/named-characters.html>.
+        match reference {
+            "AMP" => 0x26,
+            "LT" => 0x3C,
+            "GT" => 0x3E,
+            "QUOT" => 0x22,
+            _ => return None,
+        }
+    };
+
+    NAMED_REFERENCES
+        .iter()
+        .find(|(_, named)| *named == code_point)
+        .map(|(name, _)| (reference.len() + "&;".len(), *name))
+}
+
+#[cfg(feature = "std")]
+fn parse_digits(digits: &str, radix: u32) -> Option<u32> {
+    if digits.is_empty() || !digits.chars().all(|char| char.is_digit(radix)) {
+        return None;
+    }
+
+    u32::from_str_radix(digits, radix).ok()
+}
+
+/// Named (e.g. `&amp;`), decimal (e.g. `&#38;`) or hexadecimal (e.g. `&#x26;`) reference.
+///
+/// Named references aren't checked against the spec's list.
+fn starts_with_character_reference(text: &str) -> bool {
+    let Some(text) = text.strip_prefix('&') else {
+        return false;
+    };
+
+    let (digits, is_digit): (_, fn(&char) -> bool) =
+        if let Some(hex) = text.strip_prefix("#x").or_else(|| text.strip_prefix("#X")) {
+            (hex, char::is_ascii_hexdigit)
+        } else if let Some(decimal) = text.strip_prefix('#') {
+            (decimal, char::is_ascii_digit)
+        } else if text.starts_with(|char: char| char.is_ascii_alphabetic()) {
+            (text, char::is_ascii_alphanumeric)
+        } else {
+            return false;
+        };
+
+    let length = digits.chars().take_while(is_digit).count();
+
+    length > 0 && digits[length..].starts_with(';')
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use super::normalize_character_references;
+    use super::{escape_attribute_value, escape_text};
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("Hello world"), "Hello world");
+        assert_eq!(escape_text("a < b && c > d"), "a &lt; b &amp;&amp; c > d");
+        assert_eq!(
+            escape_text("&amp; &nbsp; &#38; &#x26; &#X26;"),
+            "&amp; &nbsp; &#38; &#x26; &#X26;"
+        );
+        assert_eq!(
+            escape_text("AT&T &; &#; &#x; &#xZ; &amp"),
+            "AT&amp;T &amp;; &amp;#; &amp;#x; &amp;#xZ; &amp;amp"
+        );
+    }
+
+    #[test]
+    fn test_escape_attribute_value() {
+        assert_eq!(
+            escape_attribute_value(r#"a "b" 'c' <d> &e"#, Some('"')),
+            r#"a &quot;b&quot; 'c' &lt;d> &amp;e"#
+        );
+        assert_eq!(
+            escape_attribute_value(r#"a "b" 'c'"#, Some('\'')),
+            r#"a "b" &#39;c&#39;"#
+        );
+        assert_eq!(escape_attribute_value("a&b", None), "a&amp;b");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_normalize_character_references() {
+        assert_eq!(
+            normalize_character_references("&#38; &#x26; &#X026; &AMP; &amp;"),
+            "&amp; &amp; &amp; &amp; &amp;"
+        );
+        assert_eq!(
+            normalize_character_references("&#60;&#62;&#34;&#160;&LT;&QUOT;"),
+            "&lt;&gt;&quot;&nbsp;&lt;&quot;"
+        );
+        assert_eq!(
+            normalize_character_references("&#39; &#233; &copy; &Amp; &#; &#x; &#38 & &#+38;"),
+            "&#39; &#233; &copy; &Amp; &#; &#x; &#38 & &#+38;"
+        );
+        assert_eq!(
+            normalize_character_references("&#99999999999999999999;"),
+            "&#99999999999999999999;"
+        );
+    }
+}
@@ -1,240 +1,3949 @@
-use pretty::{Arena, DocAllocator, DocBuilder};
+use std::{borrow::Cow, fmt, ops::Range, path::Path};
+
+use pretty::{Arena, DocAllocator, DocBuilder, Render, RenderAnnotated};
 
 use crate::{
-    ast::{comment::Comment, doctype::Doctype, element::Element, Node},
+    ast::{
+        bogus_comment::BogusComment,
+        cdata::CData,
+        comment::Comment,
+        doctype::Doctype,
+        element::{
+            attribute::{Attribute, AttributeValue, Quote},
+            is_escapable_raw_text_element, is_preformatted_element, Element,
+        },
+        frontmatter::Frontmatter,
+        processing_instruction::ProcessingInstruction,
+        Node,
+    },
+    configuration::{
+        AttributeCase, AttributeGroup, AttributeOrder, AttributeWrap, BooleanAttributeStyle,
+        ElementDisplay, IndentStyle, Interpolation, LineEnding, MultilineAttributeValues,
+        QuoteStyle, RemoveComments, TagCase, TextWrap, VoidSelfClose, WhitespaceSensitivity,
+    },
+    escape::{escape_attribute_value, escape_text, normalize_character_references},
+    parse::{
+        check_duplicate_attributes, strip_bom, NodeIter, ParseError, ParseErrorKind, ParseOptions,
+        BOM,
+    },
+    visit::{walk_element_mut, walk_nodes_mut, VisitorMut},
     Configuration,
 };
 
 /// Prettify the given input according to the configuration.
 ///
 /// # Errors
-/// Will return an error if parsing / printing fails.
+/// Will return an error if the configuration is invalid (see [`Configuration::validate`]) or if
+/// parsing / printing fails.
 pub fn format(input: &str, config: &Configuration) -> anyhow::Result<String> {
-    let (_, nodes) =
-        Node::parse_many(input).map_err(nom::Err::<nom::error::Error<&str>>::to_owned)?;
+    format_with_ast(input, config).map(|(output, _)| output)
+}
 
-    let alloc = Arena::<()>::new();
+/// Like [`format()`], also returning the tree that was formatted (e.g. to lint it without parsing
+/// `input` again).
+///
+/// Duplicate attributes and comments are already dealt with (see
+/// [`Configuration::duplicate_attributes`] and [`Configuration::remove_comments`]).
+///
+/// # Errors
+/// See [`format()`].
+pub fn format_with_ast<'i>(
+    input: &'i str,
+    config: &Configuration,
+) -> anyhow::Result<(String, Vec<Node<'i>>)> {
+    let (nodes, unparsed) = parse_for_format(input, config, false)?;
+
+    Ok((format_nodes(input, &nodes, unparsed, config)?, nodes))
+}
+
+/// Like [`format()`], for a whole page rather than a fragment: fails unless `input` has a doctype
+/// or an `<html>` element, see [`DocumentError`].
+///
+/// # Errors
+/// Will return an error if the document is malformed, the configuration is invalid or if
+/// parsing / printing fails.
+pub fn format_document(input: &str, config: &Configuration) -> anyhow::Result<String> {
+    let (nodes, unparsed) = parse_for_format(input, config, true)?;
+
+    format_nodes(input, &nodes, unparsed, config)
+}
+
+/// Like [`format()`], parsing and printing the top-level nodes one at a time (each along with its
+/// descendants, see [`NodeIter`]) rather than the whole tree at once, so that large inputs take
+/// less memory. The output is the same.
+///
+/// # Errors
+/// See [`format()`].
+pub fn format_streaming(input: &str, config: &Configuration) -> anyhow::Result<String> {
+    config.validate()?;
+
+    let inline_elements = inline_elements(config);
+    let options = parse_options(config, &inline_elements);
+
+    let stripped = strip_bom(input);
 
     let mut buffer = String::new();
+    let mut ignore_next = false;
+    // Whether the first and last nodes are inline-level, see
+    // [`Configuration::preserve_document_edges`].
+    let mut edges: Option<(bool, bool)> = None;
+    // Held back until the next node, so that those around removed comments are merged and none
+    // are left at the end (see `remove_comments`).
+    let mut separator: Option<Node> = None;
 
-    nodes
+    // See [`Configuration::strict`].
+    let mut unparsed = "";
+
+    for node in NodeIter::with_options(input, &options) {
+        let mut nodes = vec![match node {
+            Err(error) if !config.strict && error.kind() == &ParseErrorKind::UnexpectedEndTag => {
+                unparsed = input[error.offset()..].trim();
+                break;
+            }
+            node => node?,
+        }];
+
+        check_duplicate_attributes(input, &mut nodes, config.duplicate_attributes)?;
+
+        remove_comments(&mut nodes, config.remove_comments);
+
+        let Some(node) = nodes.pop() else {
+            continue;
+        };
+
+        if separator
+            .as_mut()
+            .is_some_and(|previous| merge_separators(previous, &node))
+        {
+            continue;
+        }
+
+        match (&mut separator, node) {
+            // Nor at the start.
+            (_, Node::BlankLines(_) | Node::Whitespace) if edges.is_none() => {}
+            (_, node @ (Node::BlankLines(_) | Node::Whitespace)) => separator = Some(node),
+            (_, node) => {
+                let previous = separator.take();
+
+                let mut check_ignore_next = ignore_next;
+
+                for node in previous.iter().chain([&node]) {
+                    check_verbatim_sibling(node, &mut check_ignore_next, stripped, config)?;
+                }
+
+                let alloc = Arena::<()>::new();
+
+                let doc = previous
+                    .iter()
+                    .chain([&node])
+                    .map(|node| pretty_sibling(node, &mut ignore_next, stripped, &alloc, config))
+                    .reduce(DocBuilder::append)
+                    .unwrap_or_else(|| alloc.nil())
+                    .append(alloc.line_());
+
+                buffer.push_str(&render(doc, config)?.replace("\r\n", "\n"));
+
+                let inline = is_inline_level(&node, config);
+
+                edges = Some(edges.map_or((inline, inline), |(first, _)| (first, inline)));
+            }
+        }
+    }
+
+    Ok(finish(buffer, input, edges, unparsed, config))
+}
+
+/// [`ParseOptions::extra_inline_elements`] along with the elements displayed inline (see
+/// [`ElementDisplay::Inline`]), for the whitespace around them to be kept.
+fn inline_elements(config: &Configuration) -> Vec<&str> {
+    config
+        .parse_options
+        .extra_inline_elements
         .iter()
-        .map(|node| pretty_node(node, &alloc, config).append(alloc.line_()))
-        .reduce(DocBuilder::append)
-        .unwrap_or_else(|| alloc.nil())
-        .render_fmt(usize::try_from(config.line_width)?, &mut buffer)?;
+        .copied()
+        .chain(
+            config
+                .element_overrides
+                .iter()
+                .filter_map(|(name, element_override)| {
+                    (element_override.display == Some(ElementDisplay::Inline)).then_some(name)
+                }),
+        )
+        .collect()
+}
 
-    Ok(buffer)
+fn parse_options<'o>(config: &Configuration, inline_elements: &'o [&'o str]) -> ParseOptions<'o> {
+    ParseOptions {
+        strict_whitespace: config.whitespace_sensitivity == WhitespaceSensitivity::Strict,
+        extra_inline_elements: inline_elements,
+        ..config.parse_options
+    }
 }
 
-fn pretty_node<'b, D, A>(
-    node: &'b Node,
-    alloc: &'b D,
+/// Parse `input` once the configuration is validated, checking it is a whole page if `document`.
+///
+/// Also returns what couldn't be parsed (e.g. from a stray end tag on), empty unless
+/// [`Configuration::strict`] is disabled.
+fn parse_for_format<'i>(
+    input: &'i str,
     config: &Configuration,
-) -> DocBuilder<'b, D, A>
-where
-    D: DocAllocator<'b, A>,
-    D::Doc: Clone,
-    A: Clone,
-{
-    match node {
-        Node::Comment(comment) => pretty_comment(comment, alloc, config),
-        Node::Doctype(doctype) => pretty_doctype(doctype, alloc),
-        Node::Element(element) => pretty_element(element, alloc, config),
-        Node::Text(text) => pretty_text(text, alloc),
+    document: bool,
+) -> anyhow::Result<(Vec<Node<'i>>, &'i str)> {
+    config.validate()?;
+
+    let inline_elements = inline_elements(config);
+    let options = parse_options(config, &inline_elements);
+
+    let (remaining, mut nodes) = Node::parse_many_with_options(strip_bom(input), &options)
+        .map_err(|error| ParseError::from_nom(input, error))?;
+
+    let unparsed = remaining.trim();
+
+    if config.strict && !unparsed.is_empty() {
+        return Err(ParseError::new(input, remaining, ParseErrorKind::UnexpectedEndTag).into());
+    }
+
+    if document {
+        check_document(&nodes)?;
     }
+
+    check_duplicate_attributes(input, &mut nodes, config.duplicate_attributes)?;
+
+    remove_comments(&mut nodes, config.remove_comments);
+
+    Ok((nodes, unparsed))
 }
 
-fn pretty_comment<'b, D, A>(
-    Comment(comment): &'b Comment,
-    alloc: &'b D,
-    config: &Configuration,
-) -> DocBuilder<'b, D, A>
-where
-    D: DocAllocator<'b, A>,
-    D::Doc: Clone,
-    A: Clone,
-{
-    if comment.is_empty() {
-        alloc.text("<!---->")
-    } else {
-        let mut buffer = alloc.nil();
+/// See [`RemoveComments`].
+fn remove_comments(nodes: &mut Vec<Node>, mode: RemoveComments) {
+    struct Remove(RemoveComments);
+
+    impl<'a> VisitorMut<'a> for Remove {
+        fn visit_element_mut(&mut self, element: &mut Element<'a>) {
+            if let Element::Normal { content, .. } = element {
+                remove_siblings(content, self.0);
+            }
+
+            walk_element_mut(self, element);
+        }
+    }
+
+    fn remove_siblings(nodes: &mut Vec<Node>, mode: RemoveComments) {
+        let length = nodes.len();
 
-        for line in comment.lines() {
-            buffer += alloc.line();
-            buffer += alloc.text(line);
+        nodes.retain(|node| match node {
+            Node::BogusComment(_) => false,
+            Node::Comment(comment) => {
+                comment.is_conditional()
+                    || comment.is_ignore()
+                    || (mode == RemoveComments::KeepImportant && comment.content().starts_with('!'))
+            }
+            _ => true,
+        });
+
+        if nodes.len() == length {
+            return;
         }
 
-        // Only indent single line comments
-        if comment.lines().nth(1).is_none() {
-            buffer = buffer.nest(isize::from(config.indent_width));
+        let is_separator = |node: &Node| matches!(node, Node::BlankLines(_) | Node::Whitespace);
+
+        // The separators that were around removed comments, e.g. blank lines both before and
+        // after one, are merged.
+        nodes.dedup_by(|next, previous| merge_separators(previous, next));
+
+        while nodes.first().is_some_and(is_separator) {
+            nodes.remove(0);
         }
 
-        if comment.lines().nth(1).is_some() {
-            buffer += alloc.hardline();
-        } else {
-            buffer += alloc.line();
+        while nodes.last().is_some_and(is_separator) {
+            nodes.pop();
         }
+    }
 
-        alloc.text("<!--").append(buffer).append("-->").group()
+    if mode == RemoveComments::Keep {
+        return;
     }
+
+    remove_siblings(nodes, mode);
+    walk_nodes_mut(&mut Remove(mode), nodes);
 }
 
-fn pretty_doctype<'b, D, A>(doctype: &'b Doctype, alloc: &'b D) -> DocBuilder<'b, D, A>
-where
-    D: DocAllocator<'b, A>,
-{
-    alloc.text(if doctype.legacy {
-        r#"<!DOCTYPE html SYSTEM "about:legacy-compat">"#
+/// Merges `next` into `previous` if both are separators, blank lines taking precedence over
+/// whitespace. Returns whether they were merged.
+fn merge_separators(previous: &mut Node, next: &Node) -> bool {
+    match (&mut *previous, next) {
+        (Node::BlankLines(count), Node::BlankLines(other)) => *count = (*count).max(*other),
+        (Node::BlankLines(_), Node::Whitespace) => {}
+        (Node::Whitespace, Node::BlankLines(count)) => *previous = Node::BlankLines(*count),
+        (Node::Whitespace, Node::Whitespace) => {}
+        _ => return false,
+    }
+
+    true
+}
+
+/// What is known about the input besides its content, see [`format_with_hint`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FormatHint<'p> {
+    /// Path of the file the input comes from, e.g. given by an editor piping its content.
+    pub path: Option<&'p Path>,
+}
+
+impl FormatHint<'_> {
+    /// Whether the input is a whole page, rather than a fragment or a template (e.g. `.vue`).
+    fn is_document(&self) -> bool {
+        self.path
+            .and_then(Path::extension)
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                ["html", "htm", "xhtml"]
+                    .iter()
+                    .any(|document| extension.eq_ignore_ascii_case(document))
+            })
+    }
+}
+
+/// [`format_document`] if `hint` says the input is a page (i.e. a `.html`, `.htm` or `.xhtml`
+/// file), [`format()`] otherwise.
+///
+/// # Errors
+/// See [`format_document`] and [`format()`].
+pub fn format_with_hint(
+    input: &str,
+    config: &Configuration,
+    hint: FormatHint,
+) -> anyhow::Result<String> {
+    if hint.is_document() {
+        format_document(input, config)
     } else {
-        "<!DOCTYPE html>"
-    })
+        format(input, config)
+    }
 }
 
-fn pretty_element<'b, D, A>(
-    start: &'b Element,
-    alloc: &'b D,
+/// Like [`format()`], only reformatting the nodes intersecting `range` (a byte range of `input`,
+/// e.g. an editor's selection), the rest of `input` is kept as-is.
+///
+/// The range is expanded to whole sibling nodes, within the innermost element whose children go
+/// on their own lines (e.g. a `<ul>`, not a `<p>`). The formatted nodes are indented like the
+/// first one is in the source. Nodes without a span (see [`Node::span`], e.g. doctypes) are never
+/// reformatted.
+///
+/// # Errors
+/// Will return an error if `range` is out of bounds or not on char boundaries, see also [`format()`].
+pub fn format_range(
+    input: &str,
+    range: Range<usize>,
     config: &Configuration,
-) -> DocBuilder<'b, D, A>
-where
-    D: DocAllocator<'b, A>,
-    D::Doc: Clone,
-    A: Clone,
-{
-    let (name, attributes, inner_nodes_if_not_void) = match start {
-        Element::Void {
-            name, attributes, ..
-        } => (*name, attributes, None),
-        Element::Normal {
-            name,
-            attributes,
-            content,
-            ..
-        } => (*name, attributes, Some(content)),
-    };
+) -> anyhow::Result<String> {
+    if input.get(range.clone()).is_none() {
+        anyhow::bail!(
+            "invalid range {range:?} for an input of {} bytes",
+            input.len()
+        );
+    }
 
-    let mut buffer = alloc.text("<").append(name);
+    // What couldn't be parsed is left as-is.
+    let (nodes, _) = parse_for_format(input, config, false)?;
 
-    if let Some(attributes) = attributes
-        .iter()
-        .map(|(name, value)| {
-            alloc.line().append(*name).append(
-                value.map(|value| alloc.text("=").append(alloc.text(value).double_quotes())),
-            )
+    // An empty range (e.g. the cursor) still intersects the node it is in.
+    let intersects = |span: &Option<Range<usize>>| {
+        span.as_ref().is_some_and(|span| {
+            span.start < range.end.max(range.start + 1) && range.start < span.end
         })
-        .reduce(DocBuilder::append)
-    {
-        buffer += attributes
-            .nest(isize::from(config.indent_width))
-            .append(alloc.line_())
-            .group();
-    }
+    };
 
-    if let Some(nodes) = inner_nodes_if_not_void {
-        let force_multiline =
-            !nodes.is_empty() && nodes.iter().all(|node| !matches!(node, Node::Text(_)));
+    let mut siblings = &nodes[..];
 
-        buffer += alloc.text(">");
+    let (selected, start, end) = loop {
+        let spans: Vec<_> = siblings.iter().map(|node| node.span(input)).collect();
 
-        if let Some(nodes) = nodes
-            .iter()
-            .map(|node| {
-                if force_multiline {
-                    alloc.hardline()
-                } else {
-                    alloc.line_()
+        let (Some(first), Some(last)) = (
+            spans.iter().position(intersects),
+            spans.iter().rposition(intersects),
+        ) else {
+            return Ok(input.to_string());
+        };
+
+        if first == last {
+            if let Node::Element(Element::Normal { name, content, .. }) = &siblings[first] {
+                let mut content_spans = content.iter().filter_map(|node| node.span(input));
+
+                if let Some(first_span) = content_spans.next() {
+                    let content_end = content_spans.next_back().unwrap_or(first_span.clone()).end;
+
+                    if first_span.start <= range.start
+                        && range.end <= content_end
+                        && is_multiline_content(content, config)
+                        && !is_preformatted_element(name)
+                        && !config.parse_options.is_raw_text_element(name)
+                        && !is_escapable_raw_text_element(name)
+                    {
+                        siblings = content;
+
+                        continue;
+                    }
                 }
-                .append(pretty_node(node, alloc, config))
-            })
-            .reduce(DocBuilder::append)
-        {
-            buffer += nodes
-                .nest(isize::from(config.indent_width))
-                .append(alloc.line_())
-                .group();
+            }
         }
 
-        buffer += alloc.text("</").append(name).append(">");
+        // The ignore comment must come along with the node it applies to.
+        let first = match siblings[..first]
+            .iter()
+            .rposition(|node| !matches!(node, Node::BlankLines(_)))
+        {
+            Some(previous)
+                if spans[previous].is_some()
+                    && matches!(&siblings[previous], Node::Comment(comment) if comment.is_ignore()) =>
+            {
+                previous
+            }
+            _ => first,
+        };
+
+        break (
+            &siblings[first..=last],
+            spans[first].as_ref().map_or(0, |span| span.start),
+            spans[last].as_ref().map_or(0, |span| span.end),
+        );
+    };
 
-        buffer.group()
-    } else {
-        buffer.append(alloc.text("/>"))
+    let line = &input[input[..start].rfind('\n').map_or(0, |index| index + 1)..start];
+    let indentation = line
+        .chars()
+        .take_while(char::is_ascii_whitespace)
+        .map(|char| {
+            if char == '\t' {
+                usize::from(config.indent_width)
+            } else {
+                1
+            }
+        })
+        .sum::<usize>();
+
+    check_verbatim(selected, input, config)?;
+
+    let alloc = Arena::<()>::new();
+
+    let mut is_first = true;
+
+    // Breaking before each node rather than after it, which would indent blank lines.
+    let doc = alloc
+        .text(" ".repeat(indentation))
+        .append(
+            alloc.concat(
+                selected
+                    .iter()
+                    .zip(pretty_siblings(selected, input, &alloc, config))
+                    .map(|(node, doc)| match node {
+                        Node::BlankLines(_) | Node::Whitespace => doc,
+                        _ if std::mem::take(&mut is_first) => doc,
+                        _ => alloc.line_().append(doc),
+                    }),
+            ),
+        )
+        .nest(isize::try_from(indentation)?);
+
+    let mut buffer = render(doc, config)?.replace("\r\n", "\n");
+
+    // Already in the source, only there for the width to be right.
+    buffer.replace_range(..indentation, "");
+
+    if is_crlf(input, config) {
+        buffer = buffer.replace('\n', "\r\n");
     }
+
+    Ok([&input[..start], &buffer, &input[end..]].concat())
 }
 
-fn pretty_text<'b, D, A>(text: &'b str, alloc: &'b D) -> DocBuilder<'b, D, A>
-where
-    D: DocAllocator<'b, A>,
-    D::Doc: Clone,
-    A: Clone,
-{
-    if text.is_empty() {
-        alloc.nil()
-    } else {
-        alloc.reflow(text)
+/// Malformed document, see [`format_document`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DocumentError {
+    /// Neither a doctype nor an `<html>` element, i.e. a fragment.
+    NotADocument,
+    /// More than one doctype.
+    DuplicateDoctype,
+    /// A doctype preceded by something other than comments.
+    MisplacedDoctype,
+    /// Something other than comments next to the `<html>` element.
+    ContentOutsideRoot,
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotADocument => "not a document, missing a doctype and an `<html>` element",
+            Self::DuplicateDoctype => "more than one doctype",
+            Self::MisplacedDoctype => "the doctype must come first",
+            Self::ContentOutsideRoot => "only comments may be outside of the `<html>` element",
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::{ffi::OsStr, path::PathBuf};
+impl std::error::Error for DocumentError {}
 
-    use crate::{configuration::Configuration, format};
+fn check_document(nodes: &[Node]) -> Result<(), DocumentError> {
+    let is_ignorable = |node: &Node| {
+        matches!(
+            node,
+            Node::BlankLines(_) | Node::Comment(_) | Node::Frontmatter(_) | Node::Whitespace
+        )
+    };
+    let is_root = |node: &Node| matches!(node, Node::Element(element) if element.name().eq_ignore_ascii_case("html"));
 
-    #[test]
-    fn check_tests_dir() -> anyhow::Result<()> {
-        const ANSI_RESET: &str = "\x1b[0m";
-        const ANSI_RED: &str = "\x1b[31m";
-        const ANSI_GREEN: &str = "\x1b[32m";
-        const ANSI_BOLD_GREEN: &str = "\x1b[1;32m";
+    let mut doctypes = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| matches!(node, Node::Doctype(_)));
 
-        let configuration = Configuration {
-            line_width: 80,
-            indent_width: 2,
-        };
+    let doctype = doctypes.next().map(|(index, _)| index);
 
-        let mut failed = false;
+    if doctypes.next().is_some() {
+        return Err(DocumentError::DuplicateDoctype);
+    }
 
-        for entry in std::fs::read_dir(
-            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests"]
-                .into_iter()
-                .collect::<PathBuf>(),
-        )? {
-            let path = entry?.path();
+    if let Some(index) = doctype {
+        if !nodes[..index].iter().all(is_ignorable) {
+            return Err(DocumentError::MisplacedDoctype);
+        }
+    }
 
-            match path.extension().and_then(OsStr::to_str) {
-                Some("html") => {}
-                _ => continue,
-            }
+    let after_doctype = &nodes[doctype.map_or(0, |index| index + 1)..];
 
-            println!(
-                "{ANSI_BOLD_GREEN}{:>12}{ANSI_RESET} format test file ({})",
-                "Checking",
-                path.display()
-            );
+    if after_doctype.iter().any(is_root) {
+        // Elements may otherwise be implicitly in `<html>` (e.g. `<!DOCTYPE html><title>`).
+        if !after_doctype
+            .iter()
+            .all(|node| is_ignorable(node) || is_root(node))
+            || after_doctype.iter().filter(|node| is_root(node)).count() > 1
+        {
+            return Err(DocumentError::ContentOutsideRoot);
+        }
+    } else if doctype.is_none() {
+        return Err(DocumentError::NotADocument);
+    }
 
-            let raw = std::fs::read_to_string(&path)?;
-            let pretty = format(&raw, &configuration)?;
+    Ok(())
+}
 
-            if raw != pretty {
-                use similar::{ChangeTag, TextDiff};
+/// `unparsed` is appended verbatim, see [`Configuration::strict`].
+fn format_nodes(
+    input: &str,
+    nodes: &[Node],
+    unparsed: &str,
+    config: &Configuration,
+) -> anyhow::Result<String> {
+    let stripped = strip_bom(input);
 
-                failed = true;
+    check_verbatim(nodes, stripped, config)?;
 
-                for change in TextDiff::from_lines(&raw, &pretty).iter_all_changes() {
-                    match change.tag() {
-                        ChangeTag::Delete => print!("{ANSI_RED}-{change}{ANSI_RESET}"),
-                        ChangeTag::Insert => print!("{ANSI_GREEN}+{change}{ANSI_RESET}"),
-                        ChangeTag::Equal => print!(" {change}"),
-                    }
-                }
-            }
-        }
+    let alloc = Arena::<()>::new();
 
-        if failed {
-            Err(anyhow::anyhow!("At least one check failed"))
-        } else {
-            Ok(())
-        }
+    let doc = nodes
+        .iter()
+        .zip(pretty_siblings(nodes, stripped, &alloc, config))
+        .map(|(node, doc)| match node {
+            // Top-level nodes are on their own line anyway.
+            Node::BlankLines(_) | Node::Whitespace => doc,
+            _ => doc.append(alloc.line_()),
+        })
+        .reduce(DocBuilder::append)
+        .unwrap_or_else(|| alloc.nil());
+
+    // Verbatim content (e.g. `<pre>`) may contain either line ending.
+    let buffer = render(doc, config)?.replace("\r\n", "\n");
+
+    let edges = nodes.first().zip(nodes.last()).map(|(first, last)| {
+        (
+            is_inline_level(first, config),
+            is_inline_level(last, config),
+        )
+    });
+
+    Ok(finish(buffer, input, edges, unparsed, config))
+}
+
+/// The printed top-level nodes (with `\n` line endings) along with what surrounds them: `edges`
+/// are whether the first and last nodes are inline-level (see
+/// [`Configuration::preserve_document_edges`]), `unparsed` is appended verbatim (see
+/// [`Configuration::strict`]).
+fn finish(
+    mut buffer: String,
+    input: &str,
+    edges: Option<(bool, bool)>,
+    unparsed: &str,
+    config: &Configuration,
+) -> String {
+    let bom = config.preserve_bom && input.starts_with(BOM);
+    let input = strip_bom(input);
+
+    buffer.truncate(buffer.trim_end_matches('\n').len());
+
+    push_unparsed(&mut buffer, unparsed);
+
+    if let (true, Some((first, last))) = (config.preserve_document_edges, edges) {
+        let is_whitespace = |char: char| char.is_ascii_whitespace();
+
+        if first && input.starts_with(is_whitespace) {
+            buffer.insert(0, ' ');
+        }
+
+        if last && unparsed.is_empty() && input.ends_with(is_whitespace) {
+            buffer.push(' ');
+        }
+    }
+
+    if config.insert_final_newline && !buffer.is_empty() {
+        buffer.push('\n');
+    }
+
+    if bom {
+        buffer.insert(0, BOM);
+    }
+
+    if is_crlf(input, config) {
+        buffer.replace('\n', "\r\n")
+    } else {
+        buffer
+    }
+}
+
+/// On its own line, see [`Configuration::strict`].
+fn push_unparsed(buffer: &mut String, unparsed: &str) {
+    if unparsed.is_empty() {
+        return;
+    }
+
+    if !buffer.is_empty() {
+        buffer.push('\n');
+    }
+
+    buffer.push_str(&unparsed.replace("\r\n", "\n"));
+}
+
+fn render<'b>(doc: DocBuilder<'b, Arena<'b>>, config: &Configuration) -> anyhow::Result<String> {
+    let mut buffer = String::new();
+
+    let width = usize::try_from(config.line_width)?;
+
+    match config.indent_style {
+        IndentStyle::Spaces => doc.render_fmt(width, &mut buffer)?,
+        IndentStyle::Tabs => doc.render_raw(
+            width,
+            &mut TabIndentWrite {
+                upstream: &mut buffer,
+                indent_width: usize::from(config.indent_width),
+                indentation: None,
+            },
+        )?,
+    }
+
+    Ok(buffer)
+}
+
+/// Whether the output should use CRLF line endings, see [`Configuration::line_ending`].
+fn is_crlf(input: &str, config: &Configuration) -> bool {
+    match config.line_ending {
+        LineEnding::Lf => false,
+        LineEnding::Crlf => true,
+        LineEnding::Auto => {
+            let crlf_count = input.matches("\r\n").count();
+
+            crlf_count > input.matches('\n').count() - crlf_count
+        }
+    }
+}
+
+/// Check whether the given input is already formatted according to the configuration.
+///
+/// # Errors
+/// Will return an error if parsing / printing fails.
+pub fn check(input: &str, config: &Configuration) -> anyhow::Result<bool> {
+    Ok(format(input, config)? == input)
+}
+
+/// Format the given input, then format the result again and assert both are the same, i.e. that
+/// formatting is idempotent for this input. Returns the formatted input.
+///
+/// # Errors
+/// Will return an error if parsing / printing fails.
+///
+/// # Panics
+/// Will panic if the second pass changes the output of the first one.
+pub fn assert_idempotent(input: &str, config: &Configuration) -> anyhow::Result<String> {
+    let once = format(input, config)?;
+    let twice = format(&once, config)?;
+
+    assert_eq!(once, twice, "formatting is not idempotent for {input:?}");
+
+    Ok(once)
+}
+
+/// Writes indentation using tabs, `pretty` only ever indents using spaces.
+struct TabIndentWrite<'a> {
+    upstream: &'a mut String,
+    indent_width: usize,
+    /// Spaces written since the last newline, if nothing else was written since.
+    indentation: Option<usize>,
+}
+
+impl Render for TabIndentWrite<'_> {
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<usize, Self::Error> {
+        if let Some(indentation) = self.indentation.as_mut() {
+            if !s.is_empty() && s.bytes().all(|byte| byte == b' ') {
+                *indentation += s.len();
+
+                return Ok(s.len());
+            }
+
+            if *indentation > 0 {
+                let (tabs, spaces) = (
+                    *indentation / self.indent_width,
+                    *indentation % self.indent_width,
+                );
+
+                self.upstream.push_str(&"\t".repeat(tabs));
+                self.upstream.push_str(&" ".repeat(spaces));
+            }
+        }
+
+        // `pretty` writes newlines on their own, followed by the indentation.
+        self.indentation = (s == "\n").then_some(0);
+        self.upstream.push_str(s);
+
+        Ok(s.len())
+    }
+
+    fn fail_doc(&self) -> Self::Error {
+        fmt::Error
+    }
+}
+
+impl RenderAnnotated<'_, ()> for TabIndentWrite<'_> {
+    fn push_annotation(&mut self, _: &()) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Each of the sibling `nodes`, those following an ignore comment (see [`Comment::is_ignore`])
+/// or preserved (see [`ElementDisplay::Preserve`]) are kept as they are in the source.
+fn pretty_siblings<'b, D, A>(
+    nodes: &'b [Node],
+    input: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> Vec<DocBuilder<'b, D, A>>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    let mut ignore_next = false;
+
+    nodes
+        .iter()
+        .map(|node| pretty_sibling(node, &mut ignore_next, input, alloc, config))
+        .collect()
+}
+
+/// See [`pretty_siblings`], `ignore_next` being whether the previous sibling is an ignore comment
+/// (blank lines aside).
+fn pretty_sibling<'b, D, A>(
+    node: &'b Node,
+    ignore_next: &mut bool,
+    input: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    // Those that can't be located were reported by `check_verbatim`.
+    match node.span(input) {
+        Some(span) if is_verbatim(node, ignore_next, config) => alloc.text(&input[span]),
+        _ => pretty_node(node, input, alloc, config),
+    }
+}
+
+/// Whether `node` is kept as it is in the source, see [`pretty_sibling`].
+fn is_verbatim(node: &Node, ignore_next: &mut bool, config: &Configuration) -> bool {
+    match node {
+        Node::BlankLines(_) => false,
+        node => {
+            std::mem::replace(
+                ignore_next,
+                matches!(node, Node::Comment(comment) if comment.is_ignore()),
+            ) || matches!(node, Node::Element(element)
+                if config.element_override(element.name()).display
+                    == Some(ElementDisplay::Preserve))
+        }
+    }
+}
+
+/// Fail if any of `nodes` (or their descendants) is to be kept as it is in the source (see
+/// [`pretty_siblings`]) but can't be located in `input` (e.g. a doctype), rather than formatting
+/// it anyway.
+fn check_verbatim(nodes: &[Node], input: &str, config: &Configuration) -> anyhow::Result<()> {
+    let mut ignore_next = false;
+
+    nodes
+        .iter()
+        .try_for_each(|node| check_verbatim_sibling(node, &mut ignore_next, input, config))
+}
+
+/// See [`check_verbatim`] and [`pretty_sibling`].
+fn check_verbatim_sibling(
+    node: &Node,
+    ignore_next: &mut bool,
+    input: &str,
+    config: &Configuration,
+) -> anyhow::Result<()> {
+    if is_verbatim(node, ignore_next, config) {
+        if node.span(input).is_none() {
+            anyhow::bail!("`{node}` can't be kept as-is, it can't be located in the input");
+        }
+    } else if let Node::Element(element) = node {
+        check_verbatim(element.content(), input, config)?;
+    }
+
+    Ok(())
+}
+
+fn pretty_node<'b, D, A>(
+    node: &'b Node,
+    input: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    match node {
+        Node::BlankLines(count) => pretty_blank_lines(*count, alloc, config),
+        Node::BogusComment(bogus_comment) => pretty_bogus_comment(bogus_comment, alloc),
+        Node::CData(cdata) => pretty_cdata(cdata, alloc),
+        Node::Comment(comment) => pretty_comment(comment, alloc, config),
+        Node::Doctype(doctype) => pretty_doctype(doctype, alloc),
+        Node::Element(element) => pretty_element(element, input, alloc, config, false),
+        Node::Frontmatter(frontmatter) => pretty_frontmatter(frontmatter, alloc),
+        Node::ProcessingInstruction(processing_instruction) => {
+            pretty_processing_instruction(processing_instruction, alloc)
+        }
+        Node::Text(text) => pretty_text(text, alloc, config),
+        Node::Whitespace => alloc.nil(),
+    }
+}
+
+fn pretty_blank_lines<'b, D, A>(
+    count: usize,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    // Not a `hardline`, which would indent the blank lines.
+    alloc.text("\n".repeat(count.min(usize::from(config.max_blank_lines))))
+}
+
+fn pretty_bogus_comment<'b, D, A>(
+    BogusComment(bogus_comment): &'b BogusComment,
+    alloc: &'b D,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    alloc.text("<").append(bogus_comment.as_ref()).append(">")
+}
+
+fn pretty_cdata<'b, D, A>(CData(cdata): &'b CData, alloc: &'b D) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    alloc.text("<![CDATA[").append(cdata.as_ref()).append("]]>")
+}
+
+fn pretty_comment<'b, D, A>(
+    comment: &'b Comment,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    let Comment(raw) = comment;
+    let content = comment.content();
+
+    if comment.is_conditional()
+        || (config.preserve_comment_whitespace && !content.is_empty() && !content.contains('\n'))
+    {
+        alloc.text("<!--").append(raw.as_ref()).append("-->")
+    } else if content.is_empty() {
+        alloc.text("<!---->")
+    } else if content.contains('\n') {
+        // Whitespace starting every line, e.g. both tabs and spaces.
+        let indentation = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+            .reduce(|common, other| {
+                &common[..common
+                    .bytes()
+                    .zip(other.bytes())
+                    .take_while(|(char, other)| char == other)
+                    .count()]
+            })
+            .map_or(0, str::len);
+
+        // Indented relative to the enclosing element instead of the source, keeping the relative
+        // indentation of the lines (e.g. of ASCII art).
+        let lines = alloc
+            .concat(content.lines().map(|line| {
+                if line.trim().is_empty() {
+                    // Not a `hardline`, which would indent the blank line.
+                    alloc.text("\n")
+                } else {
+                    alloc.hardline().append(&line[indentation..])
+                }
+            }))
+            .nest(isize::from(config.indent_width));
+
+        alloc
+            .text("<!--")
+            .append(lines)
+            .append(alloc.hardline())
+            .append("-->")
+    } else {
+        let content = if config.reflow_comments && !content.starts_with('!') {
+            reflow_comment(content, alloc)
+        } else {
+            alloc.text(content)
+        };
+
+        // Indented relative to the enclosing element, when broken.
+        alloc
+            .text("<!--")
+            .append(
+                alloc
+                    .line()
+                    .append(content)
+                    .nest(isize::from(config.indent_width)),
+            )
+            .append(alloc.line())
+            .append("-->")
+            .group()
+    }
+}
+
+/// Words of a single line comment `content`, broken at whitespace where they don't fit. The
+/// whitespace between them is kept otherwise.
+fn reflow_comment<'b, D, A>(content: &'b str, alloc: &'b D) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    let mut doc = alloc.nil();
+    let mut rest = content;
+
+    while let Some(start) = rest.find(|char: char| char.is_ascii_whitespace()) {
+        let end = rest[start..]
+            .find(|char: char| !char.is_ascii_whitespace())
+            .map_or(rest.len(), |length| start + length);
+
+        doc = doc.append(&rest[..start]).append(
+            alloc
+                .hardline()
+                .flat_alt(alloc.text(&rest[start..end]))
+                .group(),
+        );
+
+        rest = &rest[end..];
+    }
+
+    doc.append(rest)
+}
+
+fn pretty_doctype<'b, D, A>(doctype: &'b Doctype, alloc: &'b D) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    alloc.text(doctype.to_string())
+}
+
+/// Content of `preformatted` elements is emitted verbatim.
+fn pretty_element<'b, D, A>(
+    start: &'b Element,
+    input: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+    preformatted: bool,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    let (name, attributes, inner_nodes_if_not_void) = match start {
+        Element::Void {
+            name, attributes, ..
+        } => (name.as_ref(), attributes, None),
+        Element::Normal {
+            name,
+            attributes,
+            content,
+            ..
+        } => (name.as_ref(), attributes, Some(content)),
+    };
+
+    let tag_name = pretty_tag_name(name, config);
+
+    let mut buffer = alloc.text("<").append(alloc.text(tag_name.clone()));
+
+    let element_override = config.element_override(name);
+
+    let one_per_line = match element_override.attribute_wrap {
+        Some(AttributeWrap::OnePerLine) => true,
+        Some(AttributeWrap::Fit) => false,
+        None => {
+            config
+                .max_attributes_inline
+                .is_some_and(|max| attributes.len() > usize::from(max))
+                || (config.break_attributes_with_content
+                    && attributes.len() > 1
+                    && inner_nodes_if_not_void.is_some_and(|nodes| {
+                        // Otherwise the content is kept as-is, see below.
+                        !(preformatted
+                            || is_preformatted_element(name)
+                            || config.parse_options.is_raw_text_element(name)
+                            || is_escapable_raw_text_element(name))
+                            && is_multiline_content(nodes, config)
+                    }))
+        }
+    };
+
+    let mut attributes: Vec<_> = attributes.iter().collect();
+
+    if config.attribute_order == AttributeOrder::Alphabetical {
+        attributes.sort_by_cached_key(|Attribute { name, .. }| {
+            (is_directive(name), name.to_ascii_lowercase())
+        });
+    }
+
+    if let Some(grouping) = config.attribute_grouping {
+        attributes.sort_by_key(|Attribute { name, .. }| {
+            let group = attribute_group(name);
+
+            grouping
+                .iter()
+                .position(|other| *other == group)
+                .unwrap_or(grouping.len())
+        });
+    }
+
+    // An unquoted value would run into the `/` of `/>`, unless spaced.
+    let self_closing = inner_nodes_if_not_void.is_none()
+        && !(config.parse_options.is_void_element(name)
+            && config.void_self_close == VoidSelfClose::NoSlash);
+    let last = attributes.len().saturating_sub(1);
+
+    if let Some(attributes) = attributes
+        .into_iter()
+        .enumerate()
+        .map(|(index, Attribute { name, value })| {
+            let unquoted =
+                !(self_closing && index == last) || config.space_before_self_closing_slash;
+
+            if one_per_line {
+                alloc.hardline()
+            } else {
+                alloc.line()
+            }
+            .append(alloc.text(pretty_attribute_name(name, config)))
+            .append(match value {
+                Some(value) => alloc
+                    .text("=")
+                    .append(pretty_attribute_value(name, value, unquoted, alloc, config)),
+                None => pretty_boolean_attribute_value(name, alloc, config),
+            })
+        })
+        .reduce(DocBuilder::append)
+    {
+        buffer += attributes
+            .nest(isize::from(config.indent_width))
+            .append(if self_closing && config.space_before_self_closing_slash {
+                alloc.line()
+            } else {
+                alloc.line_()
+            })
+            .group();
+    } else if self_closing && config.space_before_self_closing_slash {
+        buffer += alloc.text(" ");
+    }
+
+    if let Some(nodes) = inner_nodes_if_not_void {
+        let force_multiline = is_multiline_content(nodes, config)
+            || (element_override.display == Some(ElementDisplay::Block) && !nodes.is_empty());
+
+        buffer += alloc.text(">");
+
+        // Reflowing / indenting raw text or significant whitespace could change its meaning.
+        // Raw text isn't escaped either, escapable raw text is kept as-is too.
+        if config.format_embedded_css && name.eq_ignore_ascii_case("style") {
+            if let [Node::Text(css)] = nodes.as_slice() {
+                return buffer
+                    .append(pretty_css(css, alloc, config))
+                    .append(alloc.text("</").append(alloc.text(tag_name)).append(">"));
+            }
+        }
+
+        if config.reindent_embedded_script && name.eq_ignore_ascii_case("script") {
+            if let [Node::Text(script)] = nodes.as_slice() {
+                if script.contains('\n') {
+                    return buffer
+                        .append(pretty_script(script, alloc, config))
+                        .append(alloc.text("</").append(alloc.text(tag_name)).append(">"));
+                }
+            }
+        }
+
+        if config.parse_options.is_raw_text_element(name) || is_escapable_raw_text_element(name) {
+            for node in nodes {
+                buffer += match node {
+                    Node::Text(text) => alloc.text(text.as_ref()),
+                    node => pretty_preformatted_node(node, input, alloc, config),
+                };
+            }
+
+            return buffer.append(alloc.text("</").append(alloc.text(tag_name)).append(">"));
+        }
+
+        if preformatted || is_preformatted_element(name) {
+            for node in nodes {
+                buffer += pretty_preformatted_node(node, input, alloc, config);
+            }
+
+            return buffer.append(alloc.text("</").append(alloc.text(tag_name)).append(">"));
+        }
+
+        let mut previous: Option<&Node> = None;
+        let mut before_previous: Option<&Node> = None;
+
+        if let Some(nodes) = nodes
+            .iter()
+            .zip(pretty_siblings(nodes, input, alloc, config))
+            .map(|(node, doc)| {
+                let last = previous.replace(node);
+                let before_last = std::mem::replace(&mut before_previous, last);
+
+                match (last, node) {
+                    // Followed by the next node's line break.
+                    (_, Node::BlankLines(_) | Node::Whitespace) => doc,
+                    // Next to an element laid out as a block (e.g. with `ElementDisplay::Block`),
+                    // a line break is whitespace too.
+                    (Some(Node::Whitespace), node)
+                        if force_multiline
+                            && (!is_inline_level(node, config)
+                                || before_last.is_some_and(|before_last| {
+                                    !is_inline_level(before_last, config)
+                                })) =>
+                    {
+                        alloc.hardline().append(doc)
+                    }
+                    // Significant, may break but never disappear. Text breaks on its own.
+                    (Some(Node::Whitespace), Node::Text(_)) => alloc.softline().append(doc),
+                    (Some(Node::Whitespace), _) => alloc.line().append(doc).group(),
+                    // Breaking a line there would add whitespace.
+                    (Some(previous), node)
+                        if config.whitespace_sensitivity != WhitespaceSensitivity::Ignore
+                            && is_inline_level(previous, config)
+                            && is_inline_level(node, config) =>
+                    {
+                        doc
+                    }
+                    _ if force_multiline => alloc.hardline().append(doc),
+                    _ => alloc.line_().append(doc),
+                }
+            })
+            .reduce(DocBuilder::append)
+        {
+            buffer += nodes
+                .nest(isize::from(config.indent_width))
+                .append(alloc.line_())
+                .group();
+        } else if !config.collapse_empty_elements {
+            // Breaks when the element doesn't fit.
+            buffer += alloc.line_();
+        }
+
+        buffer += alloc.text("</").append(alloc.text(tag_name)).append(">");
+
+        buffer.group()
+    } else if config.parse_options.is_void_element(name)
+        && config.void_self_close == VoidSelfClose::NoSlash
+    {
+        buffer.append(alloc.text(">"))
+    } else {
+        buffer.append(alloc.text("/>"))
+    }
+}
+
+/// Text, or an element laid out inline (see [`ElementDisplay`] and
+/// [`WhitespaceSensitivity::Strict`]).
+fn is_inline_level(node: &Node, config: &Configuration) -> bool {
+    match node {
+        Node::Element(element) => match config.element_override(element.name()).display {
+            Some(ElementDisplay::Inline) => true,
+            Some(ElementDisplay::Block) => false,
+            _ => {
+                config.whitespace_sensitivity == WhitespaceSensitivity::Strict
+                    || config.parse_options.is_inline_element(element.name())
+            }
+        },
+        node => node.is_inline_level(),
+    }
+}
+
+/// Whether the content of an element always goes on its own lines, whatever the line width.
+fn is_multiline_content(nodes: &[Node], config: &Configuration) -> bool {
+    !nodes.is_empty()
+        && (nodes
+            .iter()
+            .all(|node| !matches!(node, Node::Text(_) | Node::Whitespace))
+            // Even if mixed with text, e.g. `<ul>` with text between the `<li>`s.
+            || nodes.iter().any(|node| {
+                matches!(node, Node::Element(_)) && !is_inline_level(node, config)
+            })
+            // The ignored node is on its own line.
+            || nodes
+                .iter()
+                .any(|node| matches!(node, Node::Comment(comment) if comment.is_ignore()))
+            || (config.max_blank_lines > 0
+                && nodes.iter().any(|node| matches!(node, Node::BlankLines(_)))))
+}
+
+/// Re-indent each line of `css` by its nesting depth, not aware of strings or comments.
+fn pretty_css<'b, D, A>(css: &'b str, alloc: &'b D, config: &Configuration) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    if css.trim().is_empty() {
+        return alloc.nil();
+    }
+
+    let mut buffer = alloc.nil();
+    let mut depth: usize = 0;
+    let mut blank_line = false;
+
+    for line in css
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+    {
+        if line.is_empty() {
+            blank_line = true;
+            continue;
+        }
+
+        if std::mem::take(&mut blank_line) {
+            // Without indentation, i.e. trailing whitespace.
+            buffer += alloc.text("\n");
+        }
+
+        let closing = line.chars().take_while(|&char| char == '}').count();
+        let indentation = depth.saturating_sub(closing) * usize::from(config.indent_width);
+
+        buffer += alloc
+            .hardline()
+            .append(line)
+            .nest(isize::try_from(indentation).unwrap_or(isize::MAX));
+
+        depth = (depth + line.matches('{').count()).saturating_sub(line.matches('}').count());
+    }
+
+    buffer
+        .nest(isize::from(config.indent_width))
+        .append(alloc.hardline())
+}
+
+/// Shift the lines of `script` to the element's indentation, keeping their relative indentation.
+/// Lines starting inside a template literal (e.g. `` `a\nb` ``) are kept as-is.
+fn pretty_script<'b, D, A>(
+    script: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    let mut in_template_literal = false;
+
+    let lines: Vec<(&str, bool)> = script
+        .lines()
+        .map(|line| {
+            let verbatim = in_template_literal;
+
+            // Not aware of strings or comments containing backticks.
+            let mut escaped = false;
+            for char in line.chars() {
+                match char {
+                    '`' if !escaped => in_template_literal = !in_template_literal,
+                    '\\' => {
+                        escaped = !escaped;
+                        continue;
+                    }
+                    _ => {}
+                }
+                escaped = false;
+            }
+
+            (line, verbatim)
+        })
+        .collect();
+
+    let is_blank = |&(line, verbatim): &(&str, bool)| !verbatim && line.trim().is_empty();
+
+    let Some(start) = lines.iter().position(|line| !is_blank(line)) else {
+        return alloc.nil();
+    };
+    let end = lines
+        .iter()
+        .rposition(|line| !is_blank(line))
+        .unwrap_or(start);
+    let lines = &lines[start..=end];
+
+    let indentation = lines
+        .iter()
+        .filter(|line| !is_blank(line) && !line.1)
+        .map(|(line, _)| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+
+    let mut buffer = alloc.nil();
+
+    for line in lines {
+        buffer += match line {
+            (line, true) => alloc.text(format!("\n{line}")),
+            // Without indentation, i.e. trailing whitespace.
+            line if is_blank(line) => alloc.text("\n"),
+            (line, false) => alloc.hardline().append(&line[indentation..]),
+        };
+    }
+
+    buffer
+        .nest(isize::from(config.indent_width))
+        .append(alloc.hardline())
+}
+
+fn pretty_frontmatter<'b, D, A>(
+    Frontmatter(frontmatter): &'b Frontmatter,
+    alloc: &'b D,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    // Followed by a blank line, without indentation.
+    alloc.text(format!("---\n{frontmatter}---\n"))
+}
+
+fn pretty_processing_instruction<'b, D, A>(
+    ProcessingInstruction(processing_instruction): &'b ProcessingInstruction,
+    alloc: &'b D,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    alloc
+        .text("<?")
+        .append(processing_instruction.as_ref())
+        .append("?>")
+}
+
+fn pretty_tag_name<'b>(name: &'b str, config: &Configuration) -> Cow<'b, str> {
+    // Most likely a component (e.g. `<MyComponent>`) or a foreign element (e.g. `<svg:rect>`).
+    let case_sensitive = name.contains(':')
+        || (name.contains(|char: char| char.is_ascii_uppercase())
+            && name.contains(|char: char| char.is_ascii_lowercase()));
+
+    match config.tag_case {
+        TagCase::Lower if !case_sensitive => Cow::Owned(name.to_ascii_lowercase()),
+        TagCase::Upper if !case_sensitive => Cow::Owned(name.to_ascii_uppercase()),
+        _ => Cow::Borrowed(name),
+    }
+}
+
+/// Duplicate attributes are expected to have been dealt with, lowercasing can't introduce any.
+fn pretty_attribute_name<'b>(name: &'b str, config: &Configuration) -> Cow<'b, str> {
+    // Framework directives and namespaced attributes (e.g. `xlink:href`) may be case-sensitive.
+    if config.attribute_case == AttributeCase::Preserve
+        || is_directive(name)
+        || name.contains(':')
+        || !name.contains(|char: char| char.is_ascii_uppercase())
+    {
+        return Cow::Borrowed(name);
+    }
+
+    Cow::Owned(name.to_ascii_lowercase())
+}
+
+/// Framework (Vue, Alpine, Angular, ...) directive, e.g. `:class`, `@click` or `v-if`.
+fn is_directive(name: &str) -> bool {
+    name.starts_with([':', '@', '#', '[', '(', '*']) || name.starts_with("v-")
+}
+
+fn attribute_group(name: &str) -> AttributeGroup {
+    let name = name.to_ascii_lowercase();
+
+    if name.starts_with('@') || name.starts_with("v-on:") || name.starts_with("x-on:") {
+        AttributeGroup::Event
+    } else if name.starts_with(':') || name.starts_with("v-bind:") || name.starts_with("x-bind:") {
+        AttributeGroup::Binding
+    } else if name.starts_with('#') || name.starts_with("v-") || name.starts_with("x-") {
+        AttributeGroup::Directive
+    } else {
+        AttributeGroup::Plain
+    }
+}
+
+fn pretty_preformatted_node<'b, D, A>(
+    node: &'b Node,
+    input: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    match node {
+        Node::Element(element) => pretty_element(element, input, alloc, config, true),
+        Node::Text(text) => alloc.text(normalize_entities(escape_text(text), config)),
+        node => pretty_node(node, input, alloc, config),
+    }
+}
+
+/// `unquoted` allows keeping unquoted values as such, see
+/// [`Configuration::quote_unquotable_values`].
+fn pretty_attribute_value<'b, D, A>(
+    name: &str,
+    value: &'b AttributeValue,
+    unquoted: bool,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    if unquoted
+        && !config.quote_unquotable_values
+        && value.quote == Quote::Unquoted
+        && value.is_unquotable()
+    {
+        return pretty_str(
+            normalize_entities(escape_attribute_value(&value.text, None), config),
+            alloc,
+        );
+    }
+
+    let quote = match (config.quote_style, value.quote) {
+        (QuoteStyle::Single, _) | (QuoteStyle::Preserve, Quote::Single) => '\'',
+        (QuoteStyle::Double | QuoteStyle::Preserve, _) => '"',
+    };
+
+    let other = if quote == '"' { '\'' } else { '"' };
+
+    // Fallback to the other quote if the value only contains the preferred one, escape otherwise.
+    let quote = if value.text.contains(quote) && !value.text.contains(other) {
+        other
+    } else {
+        quote
+    };
+
+    let mut text = normalize_entities(escape_attribute_value(&value.text, Some(quote)), config);
+
+    // Escaping never adds whitespace, nor templating.
+    if config.sort_class_tokens && name.eq_ignore_ascii_case("class") {
+        text = sort_class_tokens(text);
+    }
+
+    let text = if text.contains('\n') {
+        match config.multiline_attribute_values {
+            MultilineAttributeValues::Preserve => pretty_str(text, alloc),
+            MultilineAttributeValues::Collapse => pretty_str(collapse_newlines(&text), alloc),
+            MultilineAttributeValues::Indent => {
+                pretty_multiline_attribute_value(&text, alloc, config)
+            }
+        }
+    } else {
+        pretty_str(text, alloc)
+    };
+
+    if quote == '"' {
+        text.double_quotes()
+    } else {
+        text.single_quotes()
+    }
+}
+
+fn collapse_newlines(text: &str) -> String {
+    let mut buffer = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|char: char| char.is_ascii_whitespace()) {
+        buffer.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest
+            .find(|char: char| !char.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+
+        buffer.push_str(if rest[..end].contains('\n') {
+            " "
+        } else {
+            &rest[..end]
+        });
+        rest = &rest[end..];
+    }
+
+    buffer.push_str(rest);
+    buffer
+}
+
+fn pretty_multiline_attribute_value<'b, D, A>(
+    text: &str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    let mut lines: Vec<_> = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    let is_blank = |line: &str| line.trim_start_matches([' ', '\t']).is_empty();
+
+    let first = lines.remove(0);
+    let closing = lines.pop_if(|line| is_blank(line)).is_some();
+
+    let indentation = lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+
+    let mut buffer = alloc.nil();
+
+    for line in lines {
+        buffer += if is_blank(line) {
+            // Without indentation, i.e. trailing whitespace.
+            alloc.text("\n")
+        } else {
+            alloc
+                .hardline()
+                .append(pretty_str(line[indentation..].to_owned(), alloc))
+        };
+    }
+
+    let buffer =
+        pretty_str(first.to_owned(), alloc).append(buffer.nest(isize::from(config.indent_width)));
+
+    if closing {
+        buffer.append(alloc.hardline())
+    } else {
+        buffer
+    }
+}
+
+fn pretty_boolean_attribute_value<'b, D, A>(
+    name: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    let value = match config.boolean_attribute_style {
+        BooleanAttributeStyle::Minimal => return alloc.nil(),
+        BooleanAttributeStyle::Empty => alloc.nil(),
+        BooleanAttributeStyle::Explicit => alloc.text(pretty_attribute_name(name, config)),
+    };
+
+    let value = if config.quote_style == QuoteStyle::Single {
+        value.single_quotes()
+    } else {
+        value.double_quotes()
+    };
+
+    alloc.text("=").append(value)
+}
+
+/// Sorted and deduped (ASCII) whitespace-separated tokens, unless templated.
+fn sort_class_tokens(value: Cow<'_, str>) -> Cow<'_, str> {
+    if ["{{", "{%", "<%", "${"]
+        .iter()
+        .any(|open| value.contains(open))
+    {
+        return value;
+    }
+
+    let mut tokens: Vec<_> = value.split_ascii_whitespace().collect();
+
+    tokens.sort_unstable();
+    tokens.dedup();
+
+    let sorted = tokens.join(" ");
+
+    if sorted == value {
+        value
+    } else {
+        Cow::Owned(sorted)
+    }
+}
+
+fn pretty_text<'b, D, A>(
+    text: &'b str,
+    alloc: &'b D,
+    config: &Configuration,
+) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+    D::Doc: Clone,
+    A: Clone,
+{
+    if text.is_empty() {
+        alloc.nil()
+    } else {
+        // Unlike `DocAllocator::reflow`, only break on HTML (i.e. ASCII) whitespace: a literal
+        // no-break space must stay put. Character references (e.g. `&nbsp;`) never contain
+        // whitespace and are thus never broken.
+        let words = |text| {
+            words(text, config.interpolation)
+                .map(|word| pretty_str(normalize_entities(escape_text(word), config), alloc))
+        };
+
+        match config.text_wrap {
+            TextWrap::Reflow => alloc.intersperse(words(text), alloc.softline()),
+            TextWrap::Preserve => alloc.intersperse(
+                text.lines()
+                    .filter(|line| !line.trim_ascii().is_empty())
+                    .map(|line| alloc.intersperse(words(line), alloc.space())),
+                alloc.hardline(),
+            ),
+            TextWrap::NoWrap => alloc.intersperse(words(text), alloc.space()),
+        }
+    }
+}
+
+/// See [`Configuration::normalize_entities`], `text` being escaped already.
+fn normalize_entities<'t>(text: Cow<'t, str>, config: &Configuration) -> Cow<'t, str> {
+    if !config.normalize_entities {
+        return text;
+    }
+
+    match normalize_character_references(&text) {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(normalized) => Cow::Owned(normalized),
+    }
+}
+
+/// (ASCII) whitespace-separated words of `text`, interpolations (e.g. `{{ a }}`) being part of
+/// a single word along with their whitespace.
+fn words(text: &str, interpolation: Interpolation) -> impl Iterator<Item = &str> {
+    let delimiters: &[(&str, &str)] = match interpolation {
+        Interpolation::None => &[],
+        Interpolation::Mustache => &[("{{", "}}")],
+        Interpolation::MustacheAndDollar => &[("{{", "}}"), ("${", "}")],
+    };
+
+    let mut remaining = text.trim_start_matches(|char: char| char.is_ascii_whitespace());
+
+    std::iter::from_fn(move || {
+        if remaining.is_empty() {
+            return None;
+        }
+
+        let mut end = 0;
+
+        while let Some(char) = remaining[end..].chars().next() {
+            if char.is_ascii_whitespace() {
+                break;
+            }
+
+            // Unclosed interpolations are regular text.
+            end += delimiters
+                .iter()
+                .find_map(|(open, close)| {
+                    let after_open = remaining[end..].strip_prefix(open)?;
+
+                    Some(open.len() + after_open.find(close)? + close.len())
+                })
+                .unwrap_or(char.len_utf8());
+        }
+
+        let (word, rest) = remaining.split_at(end);
+
+        remaining = rest.trim_start_matches(|char: char| char.is_ascii_whitespace());
+
+        Some(word)
+    })
+}
+
+/// Like [`DocAllocator::text`], but measured in display columns (e.g. CJK characters are two
+/// columns wide) rather than graphemes when the `unicode-width` feature is enabled.
+fn pretty_str<'b, D, A>(text: impl Into<Cow<'b, str>>, alloc: &'b D) -> DocBuilder<'b, D, A>
+where
+    D: DocAllocator<'b, A>,
+{
+    let text = text.into();
+
+    #[cfg(feature = "unicode-width")]
+    if !text.is_ascii() {
+        let width = unicode_width::UnicodeWidthStr::width(text.as_ref());
+
+        // Not `alloc.text`, which already wraps non-ASCII text with its grapheme count.
+        let text = match text {
+            Cow::Owned(text) => pretty::Doc::OwnedText(text.into()),
+            Cow::Borrowed(text) => pretty::Doc::BorrowedText(text),
+        };
+
+        return DocBuilder(
+            alloc,
+            pretty::Doc::RenderLen(width, alloc.alloc(text)).into(),
+        );
+    }
+
+    alloc.text(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::OsStr,
+        path::{Path, PathBuf},
+    };
+
+    use crate::{
+        check,
+        configuration::{
+            AttributeCase, AttributeGroup, AttributeOrder, AttributeWrap, BooleanAttributeStyle,
+            Configuration, DuplicateAttributes, ElementDisplay, ElementOverride, IndentStyle,
+            Interpolation, LineEnding, MultilineAttributeValues, QuoteStyle, RemoveComments,
+            TagCase, TextWrap, VoidSelfClose, WhitespaceSensitivity,
+        },
+        format, ParseError, ParseErrorKind, ParseOptions,
+    };
+
+    use super::{
+        assert_idempotent, format_document, format_range, format_streaming, format_with_ast,
+        format_with_hint, DocumentError, FormatHint,
+    };
+
+    #[test]
+    fn test_idempotency() -> anyhow::Result<()> {
+        let configurations = [
+            Configuration::default(),
+            Configuration {
+                line_width: 20,
+                ..Configuration::default()
+            },
+            Configuration {
+                line_width: 1,
+                indent_style: IndentStyle::Tabs,
+                ..Configuration::default()
+            },
+            Configuration {
+                max_blank_lines: 2,
+                attribute_order: AttributeOrder::Alphabetical,
+                format_embedded_css: true,
+                reindent_embedded_script: true,
+                ..Configuration::default()
+            },
+        ];
+
+        let mut inputs = vec![
+            "<div>\n\n\n<!--\n  a\n    b\n-->\n\n\n<p>x</p>\n\n</div>".to_string(),
+            "<div><div><!--\n\n  a\n\n--></div></div>".to_string(),
+            "<ul><li>a<li>b  <b>c</b>d</ul>".to_string(),
+            "<p>a <b>b</b> c <i>d</i>, <a href=\"#\">e  f</a></p>".to_string(),
+        ];
+
+        for entry in std::fs::read_dir(
+            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests"]
+                .into_iter()
+                .collect::<PathBuf>(),
+        )? {
+            let path = entry?.path();
+
+            if path.extension().and_then(OsStr::to_str) == Some("html") {
+                inputs.push(std::fs::read_to_string(&path)?);
+            }
+        }
+
+        for input in &inputs {
+            for configuration in &configurations {
+                assert_idempotent(input, configuration)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_tests_dir() -> anyhow::Result<()> {
+        const ANSI_RESET: &str = "\x1b[0m";
+        const ANSI_RED: &str = "\x1b[31m";
+        const ANSI_GREEN: &str = "\x1b[32m";
+        const ANSI_BOLD_GREEN: &str = "\x1b[1;32m";
+
+        let configuration = Configuration {
+            line_width: 80,
+            indent_width: 2,
+            ..Configuration::default()
+        };
+
+        let mut failed = false;
+
+        for entry in std::fs::read_dir(
+            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests"]
+                .into_iter()
+                .collect::<PathBuf>(),
+        )? {
+            let path = entry?.path();
+
+            match path.extension().and_then(OsStr::to_str) {
+                Some("html") => {}
+                _ => continue,
+            }
+
+            println!(
+                "{ANSI_BOLD_GREEN}{:>12}{ANSI_RESET} format test file ({})",
+                "Checking",
+                path.display()
+            );
+
+            let raw = std::fs::read_to_string(&path)?;
+            let pretty = format(&raw, &configuration)?;
+
+            if raw != pretty {
+                use similar::{ChangeTag, TextDiff};
+
+                failed = true;
+
+                for change in TextDiff::from_lines(&raw, &pretty).iter_all_changes() {
+                    match change.tag() {
+                        ChangeTag::Delete => print!("{ANSI_RED}-{change}{ANSI_RESET}"),
+                        ChangeTag::Insert => print!("{ANSI_GREEN}+{change}{ANSI_RESET}"),
+                        ChangeTag::Equal => print!(" {change}"),
+                    }
+                }
+            }
+        }
+
+        if failed {
+            Err(anyhow::anyhow!("At least one check failed"))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_quote_style() -> anyhow::Result<()> {
+        let input = r#"<div a='say("hi")' b='single' c="double" d=unquoted></div>"#;
+
+        let format_with = |quote_style| {
+            format(
+                input,
+                &Configuration {
+                    quote_style,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(QuoteStyle::Double)?,
+            "<div a='say(\"hi\")' b=\"single\" c=\"double\" d=\"unquoted\"></div>\n"
+        );
+
+        assert_eq!(
+            format_with(QuoteStyle::Single)?,
+            "<div a='say(\"hi\")' b='single' c='double' d='unquoted'></div>\n"
+        );
+
+        assert_eq!(
+            format_with(QuoteStyle::Preserve)?,
+            "<div a='say(\"hi\")' b='single' c=\"double\" d=\"unquoted\"></div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_spaced_equals() -> anyhow::Result<()> {
+        for input in [
+            r#"<div class="x"></div>"#,
+            "<div class = \"x\"></div>",
+            "<div class\n=\n\"x\"></div>",
+        ] {
+            assert_eq!(
+                format(input, &Configuration::default())?,
+                "<div class=\"x\"></div>\n"
+            );
+        }
+
+        assert!(format("<div class =></div>", &Configuration::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_unquotable_values() -> anyhow::Result<()> {
+        let input = r#"<input value=1 name="a" title=a&amp;b>"#;
+
+        let format_with = |quote_unquotable_values| {
+            format(
+                input,
+                &Configuration {
+                    quote_unquotable_values,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(true)?,
+            "<input value=\"1\" name=\"a\" title=\"a&amp;b\"/>\n"
+        );
+
+        assert_eq!(
+            // The last one would run into `/>`.
+            format_with(false)?,
+            "<input value=1 name=\"a\" title=\"a&amp;b\"/>\n"
+        );
+
+        assert_eq!(
+            format(
+                "<p title=a&amp;b>Hi</p>",
+                &Configuration {
+                    quote_unquotable_values: false,
+                    ..Configuration::default()
+                }
+            )?,
+            "<p title=a&amp;b>Hi</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_void_self_close() -> anyhow::Result<()> {
+        let input = r#"<input type="text"><img src="a.png"/><MyComponent/>"#;
+
+        let format_with = |void_self_close| {
+            format(
+                input,
+                &Configuration {
+                    void_self_close,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(VoidSelfClose::Slash)?,
+            "<input type=\"text\"/>\n<img src=\"a.png\"/>\n<MyComponent/>\n"
+        );
+
+        assert_eq!(
+            format_with(VoidSelfClose::NoSlash)?,
+            "<input type=\"text\">\n<img src=\"a.png\">\n<MyComponent/>\n"
+        );
+
+        // Self-closed elements are kept as-is, unlike void ones.
+        let input = "<template>\n  <div/>\n  <MyComponent/>\n  <MyComponent></MyComponent>\n  <input/>\n</template>\n";
+        let config = Configuration {
+            void_self_close: VoidSelfClose::NoSlash,
+            parse_options: ParseOptions {
+                self_closing_tags: true,
+                ..ParseOptions::default()
+            },
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(input, &config)?,
+            input.replace("<input/>", "<input>")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_void_self_close_extra_void_elements() -> anyhow::Result<()> {
+        let config = Configuration {
+            void_self_close: VoidSelfClose::NoSlash,
+            parse_options: ParseOptions {
+                extra_void_elements: &["x-void"],
+                ..ParseOptions::default()
+            },
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format("<x-void a=b><br/><my-icon/>", &config)?,
+            "<x-void a=\"b\">\n<br>\n<my-icon/>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_space_before_self_closing_slash() -> anyhow::Result<()> {
+        let input = r#"<br><img src=a.png><MyComponent/><p class="a">a</p><div id="b"></div>"#;
+
+        let format_with = |space_before_self_closing_slash, void_self_close| {
+            format(
+                input,
+                &Configuration {
+                    space_before_self_closing_slash,
+                    void_self_close,
+                    quote_unquotable_values: false,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(true, VoidSelfClose::Slash)?,
+            "<br />\n<img src=a.png />\n<MyComponent />\n<p class=\"a\">a</p>\n<div id=\"b\"></div>\n"
+        );
+        assert_eq!(
+            format_with(false, VoidSelfClose::Slash)?,
+            "<br/>\n<img src=\"a.png\"/>\n<MyComponent/>\n<p class=\"a\">a</p>\n<div id=\"b\"></div>\n"
+        );
+        assert_eq!(
+            format_with(true, VoidSelfClose::NoSlash)?,
+            "<br>\n<img src=a.png>\n<MyComponent />\n<p class=\"a\">a</p>\n<div id=\"b\"></div>\n"
+        );
+
+        // The slash goes on its own line when the attributes don't fit.
+        assert_eq!(
+            format(
+                r#"<img src="a.png" alt="An image"><p class="a" id="b">a</p>"#,
+                &Configuration {
+                    line_width: 20,
+                    space_before_self_closing_slash: true,
+                    ..Configuration::default()
+                }
+            )?,
+            concat!(
+                "<img\n",
+                "  src=\"a.png\"\n",
+                "  alt=\"An image\"\n",
+                "/>\n",
+                "<p class=\"a\" id=\"b\">\n",
+                "  a\n",
+                "</p>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_blank_lines() -> anyhow::Result<()> {
+        let input = "<div>\n  <p>a</p>\n\n\n  <p>b</p>\n\n  <p>c</p>\n</div>\n\n\n\n<div></div>\n";
+
+        let format_with = |max_blank_lines| {
+            format(
+                input,
+                &Configuration {
+                    max_blank_lines,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(0)?,
+            "<div>\n  <p>a</p>\n  <p>b</p>\n  <p>c</p>\n</div>\n<div></div>\n"
+        );
+
+        assert_eq!(
+            format_with(1)?,
+            "<div>\n  <p>a</p>\n\n  <p>b</p>\n\n  <p>c</p>\n</div>\n\n<div></div>\n"
+        );
+
+        assert_eq!(
+            format_with(2)?,
+            "<div>\n  <p>a</p>\n\n\n  <p>b</p>\n\n  <p>c</p>\n</div>\n\n\n<div></div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_indent_style_tabs() -> anyhow::Result<()> {
+        let configuration = Configuration {
+            indent_style: IndentStyle::Tabs,
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(
+                concat!(
+                    "<div><div><p>Hello</p></div>",
+                    "<div a=\"1\" b=\"2\" c=\"3\" d=\"4\" e=\"5\" f=\"6\" g=\"7\" h=\"8\" i=\"9\" j=\"10\" k=\"11\"></div>",
+                    "<pre>\n  keep\n</pre></div>"
+                ),
+                &configuration
+            )?,
+            concat!(
+                "<div>\n",
+                "\t<div>\n",
+                "\t\t<p>Hello</p>\n",
+                "\t</div>\n",
+                "\t<div a=\"1\" b=\"2\" c=\"3\" d=\"4\" e=\"5\" f=\"6\" g=\"7\" h=\"8\" i=\"9\" j=\"10\" k=\"11\">\n",
+                "\t</div>\n",
+                "\t<pre>\n",
+                "  keep\n",
+                "</pre>\n",
+                "</div>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_ending() -> anyhow::Result<()> {
+        let format_with = |input, line_ending| {
+            format(
+                input,
+                &Configuration {
+                    line_ending,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with("<div><p>Hi</p><pre>a\r\nb\nc</pre></div>", LineEnding::Crlf)?,
+            "<div>\r\n  <p>Hi</p>\r\n  <pre>a\r\nb\r\nc</pre>\r\n</div>\r\n"
+        );
+
+        assert_eq!(
+            format_with("<div><p>Hi</p><pre>a\r\nb</pre></div>", LineEnding::Lf)?,
+            "<div>\n  <p>Hi</p>\n  <pre>a\nb</pre>\n</div>\n"
+        );
+
+        assert_eq!(
+            format_with("<div>\r\n<p>Hi</p>\r\n</div>\n", LineEnding::Auto)?,
+            "<div>\r\n  <p>Hi</p>\r\n</div>\r\n"
+        );
+
+        assert_eq!(
+            format_with("<div>\n<p>Hi</p>\r\n</div>\n", LineEnding::Auto)?,
+            "<div>\n  <p>Hi</p>\n</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_case() -> anyhow::Result<()> {
+        let input = "<DIV><Span>a</Span><MyComponent></MyComponent><svg:rect/><BR></DIV>";
+
+        let format_with = |tag_case| {
+            format(
+                input,
+                &Configuration {
+                    tag_case,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(TagCase::Preserve)?,
+            "<DIV>\n  <Span>a</Span>\n  <MyComponent></MyComponent>\n  <svg:rect/>\n  <BR/>\n</DIV>\n"
+        );
+
+        assert_eq!(
+            format_with(TagCase::Lower)?,
+            "<div>\n  <Span>a</Span>\n  <MyComponent></MyComponent>\n  <svg:rect/>\n  <br/>\n</div>\n"
+        );
+
+        assert_eq!(
+            format_with(TagCase::Upper)?,
+            "<DIV>\n  <Span>a</Span>\n  <MyComponent></MyComponent>\n  <svg:rect/>\n  <BR/>\n</DIV>\n"
+        );
+
+        assert_eq!(
+            format(
+                "<SPAN>a</SPAN>",
+                &Configuration {
+                    tag_case: TagCase::Lower,
+                    ..Configuration::default()
+                }
+            )?,
+            "<span>a</span>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_namespaced_names() -> anyhow::Result<()> {
+        let input = concat!(
+            r#"<SVG XMLNS:XLINK="http://www.w3.org/1999/xlink">"#,
+            r##"<svg:rect xlink:href="#x"/><Svg:Use XLink:Href="#y"/>"##,
+            "</SVG>",
+        );
+
+        let format_with = |tag_case| {
+            format(
+                input,
+                &Configuration {
+                    tag_case,
+                    attribute_case: AttributeCase::Lower,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        let expected = concat!(
+            "<svg XMLNS:XLINK=\"http://www.w3.org/1999/xlink\">\n",
+            "  <svg:rect xlink:href=\"#x\"/>\n",
+            "  <Svg:Use XLink:Href=\"#y\"/>\n",
+            "</svg>\n",
+        );
+
+        assert_eq!(format_with(TagCase::Lower)?, expected);
+        assert_eq!(
+            format_with(TagCase::Upper)?,
+            expected.replace("svg ", "SVG ").replace("/svg", "/SVG")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_case() -> anyhow::Result<()> {
+        let format_with = |input, attribute_case| {
+            format(
+                input,
+                &Configuration {
+                    attribute_case,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        let input =
+            r#"<div CLASS="a" dataFoo="b" :fooBar="c" @myEvent="d" v-bind:fooBar="e">x</div>"#;
+
+        assert_eq!(
+            format_with(input, AttributeCase::Preserve)?,
+            format!("{input}\n")
+        );
+
+        assert_eq!(
+            format_with(input, AttributeCase::Lower)?,
+            "<div class=\"a\" datafoo=\"b\" :fooBar=\"c\" @myEvent=\"d\" v-bind:fooBar=\"e\">x</div>\n"
+        );
+
+        assert_eq!(
+            format(
+                r#"<div CLASS="a" class="b" ID="c">x</div>"#,
+                &Configuration {
+                    attribute_case: AttributeCase::Lower,
+                    duplicate_attributes: DuplicateAttributes::KeepFirst,
+                    ..Configuration::default()
+                }
+            )?,
+            "<div class=\"a\" id=\"c\">x</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_final_newline() -> anyhow::Result<()> {
+        let format_with = |input, insert_final_newline| {
+            format(
+                input,
+                &Configuration {
+                    insert_final_newline,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        for input in ["<p>a</p>", "<p>a</p>\n\n\n", "text\n", "<!-- a -->"] {
+            let output = format_with(input, true)?;
+            assert!(output.ends_with('\n') && !output.ends_with("\n\n"));
+
+            assert!(!format_with(input, false)?.ends_with('\n'));
+        }
+
+        assert_eq!(format_with("", true)?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> anyhow::Result<()> {
+        let config = Configuration::default();
+
+        let formatted = std::fs::read_to_string(
+            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests", "basic.html"]
+                .into_iter()
+                .collect::<PathBuf>(),
+        )?;
+
+        assert!(check(&formatted, &config)?);
+        assert!(!check(&formatted.replace("\n", ""), &config)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_attributes() -> anyhow::Result<()> {
+        let input = "<div>\n  <input name=\"a\" NAME=\"b\" type=\"text\" name=\"c\"/>\n</div>";
+
+        let error = format(input, &Configuration::default())
+            .unwrap_err()
+            .downcast::<ParseError>()?;
+
+        assert_eq!(
+            error.kind(),
+            &ParseErrorKind::DuplicateAttribute {
+                name: String::from("NAME")
+            }
+        );
+        assert_eq!((error.line(), error.column()), (2, 19));
+
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    duplicate_attributes: DuplicateAttributes::KeepFirst,
+                    ..Configuration::default()
+                }
+            )?,
+            "<div>\n  <input name=\"a\" type=\"text\"/>\n</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_character_references() -> anyhow::Result<()> {
+        let input = "<p>Tom&nbsp;&amp;&nbsp;Jerry &lt;3 caf&#233; &#x1F980; &quot;quoted&quot; \
+            fish&nbsp;&amp;&nbsp;chips a\u{a0}b &copy;&#8203;2022</p>";
+
+        for line_width in 10..=80 {
+            let output = format(
+                input,
+                &Configuration {
+                    line_width,
+                    ..Configuration::default()
+                },
+            )?;
+
+            for line in output.lines() {
+                for (index, _) in line.match_indices('&') {
+                    assert!(
+                        line[index..].contains(';'),
+                        "broken character reference in {line:?} (width {line_width})"
+                    );
+                }
+            }
+
+            assert!(output.contains("a\u{a0}b"));
+        }
+
+        assert_eq!(
+            format("<p>\n  a  \t b\n</p>", &Configuration::default())?,
+            "<p>a b</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaping() -> anyhow::Result<()> {
+        assert_eq!(
+            format(
+                r#"<p title="Tom & Jerry &amp; a<b">Fish & chips &amp; a < b</p>"#,
+                &Configuration::default()
+            )?,
+            "<p title=\"Tom &amp; Jerry &amp; a&lt;b\">Fish &amp; chips &amp; a &lt; b</p>\n"
+        );
+
+        assert_eq!(
+            format(
+                "<script>a && b < c</script><pre>a & b</pre>",
+                &Configuration::default()
+            )?,
+            "<script>a && b < c</script>\n<pre>a &amp; b</pre>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_entities() -> anyhow::Result<()> {
+        let input = concat!(
+            "<p title=\"a &#38; b\" data-x=&#x3C;>Tom &#38; Jerry &AMP; &#160;&#233;</p>\n",
+            "<pre>a &#38; b</pre>\n",
+            "<script>a = '&#38;';</script>\n",
+            "<style>a::after { content: '&#38;'; }</style>\n",
+        );
+
+        let config = Configuration {
+            normalize_entities: true,
+            quote_unquotable_values: false,
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(input, &config)?,
+            concat!(
+                "<p title=\"a &amp; b\" data-x=&lt;>Tom &amp; Jerry &amp; &nbsp;&#233;</p>\n",
+                "<pre>a &amp; b</pre>\n",
+                "<script>a = '&#38;';</script>\n",
+                "<style>a::after { content: '&#38;'; }</style>\n",
+            )
+        );
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    normalize_entities: false,
+                    ..config
+                }
+            )?,
+            input
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = format("<div>\n  <b></div>", &Configuration::default()).unwrap_err();
+
+        let error = error.downcast_ref::<ParseError>().unwrap();
+
+        assert!(matches!(
+            error.kind(),
+            ParseErrorKind::MismatchedEndTag { .. }
+        ));
+        assert_eq!((error.line(), error.column()), (2, 6));
+    }
+
+    #[test]
+    fn test_conditional_comment() -> anyhow::Result<()> {
+        let input = concat!(
+            "<!--[if lt IE 9]>\n",
+            "    <script  src=\"html5shiv.js\"></script>\n",
+            "<![endif]-->\n"
+        );
+
+        assert_eq!(format(input, &Configuration::default())?, input);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_quotes() -> anyhow::Result<()> {
+        let configuration = Configuration::default();
+
+        assert_eq!(
+            format(
+                r#"<button onclick='say("hi")' title='hello' id=main>Hi</button>"#,
+                &configuration
+            )?,
+            "<button onclick='say(\"hi\")' title=\"hello\" id=\"main\">Hi</button>\n"
+        );
+
+        assert_eq!(
+            format(r#"<div title="it's"></div>"#, &configuration)?,
+            "<div title=\"it's\"></div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inline_whitespace() -> anyhow::Result<()> {
+        let configuration = Configuration::default();
+
+        assert_eq!(
+            format(
+                "<p>Hello <b>world</b>, <a>x</a> <i>y</i>!<span>z</span></p>",
+                &configuration
+            )?,
+            "<p>Hello <b>world</b>, <a>x</a> <i>y</i>!<span>z</span></p>\n"
+        );
+
+        assert_eq!(
+            format("<div><span>a</span> <span>b</span></div>", &configuration)?,
+            "<div><span>a</span> <span>b</span></div>\n"
+        );
+
+        // Not significant around block-level elements.
+        assert_eq!(
+            format("<div><p>a</p> <p>b</p></div>", &configuration)?,
+            "<div>\n  <p>a</p>\n  <p>b</p>\n</div>\n"
+        );
+
+        // Breaking a line in place of the whitespace.
+        assert_eq!(
+            format(
+                "<p><a>x</a> <a>x</a></p>",
+                &Configuration {
+                    line_width: 10,
+                    ..Configuration::default()
+                }
+            )?,
+            "<p>\n  <a>x</a>\n  <a>x</a>\n</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore() -> anyhow::Result<()> {
+        let input = concat!(
+            "<div>\n",
+            "<p>\n",
+            "      reflowed     text\n",
+            "</p>\n",
+            "    <!-- hast-ignore -->\n",
+            "    <table>\n",
+            "      <tr><td>a</td>   <td>b</td></tr>\n",
+            "      <tr><td>c</td>   <td>d</td></tr>\n",
+            "    </table>\n",
+            "<p>after</p>\n",
+            "</div>\n",
+            "<!-- prettier-ignore -->\n",
+            "<div   class=\"x\">  keep  </div>\n",
+        );
+
+        let expected = concat!(
+            "<div>\n",
+            "  <p>reflowed text</p>\n",
+            "  <!-- hast-ignore -->\n",
+            "  <table>\n",
+            "      <tr><td>a</td>   <td>b</td></tr>\n",
+            "      <tr><td>c</td>   <td>d</td></tr>\n",
+            "    </table>\n",
+            "  <p>after</p>\n",
+            "</div>\n",
+            "<!-- prettier-ignore -->\n",
+            "<div   class=\"x\">  keep  </div>\n",
+        );
+
+        assert_eq!(format(input, &Configuration::default())?, expected);
+        assert_eq!(format(expected, &Configuration::default())?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_in_context() -> anyhow::Result<()> {
+        let input = "<svg>\n  <!-- hast-ignore -->\n  <rect   x=\"1\"/>\n</svg>";
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            "<svg>\n  <!-- hast-ignore -->\n  <rect   x=\"1\"/>\n</svg>\n"
+        );
+
+        let config = Configuration {
+            parse_options: ParseOptions {
+                self_closing_tags: true,
+                ..ParseOptions::default()
+            },
+            ..Configuration::default()
+        };
+        let input = "<div><!-- hast-ignore -->\n<div/>\n<p>after</p>\n</div>";
+        let expected = "<div>\n  <!-- hast-ignore -->\n  <div/>\n  <p>after</p>\n</div>\n";
+
+        assert_eq!(format(input, &config)?, expected);
+        assert_eq!(format_streaming(input, &config)?, expected);
+
+        // Doctypes don't keep their source.
+        let input = "<!-- hast-ignore -->\n<!doctype HTML>\n<p>a</p>";
+
+        assert!(format(input, &Configuration::default()).is_err());
+        assert!(format_streaming(input, &Configuration::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template() -> anyhow::Result<()> {
+        let configuration = Configuration::default();
+
+        assert_eq!(
+            format("<template>\n\n</template>\n<template/>", &configuration)?,
+            "<template></template>\n<template></template>\n"
+        );
+
+        assert_eq!(
+            format(
+                "<div><template><p>a</p><span>b</span></template></div>",
+                &configuration
+            )?,
+            concat!(
+                "<div>\n",
+                "  <template>\n",
+                "    <p>a</p>\n",
+                "    <span>b</span>\n",
+                "  </template>\n",
+                "</div>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_empty_elements() -> anyhow::Result<()> {
+        let input = concat!(
+            "<div></div>\n",
+            "<div class=\"x\" id=\"y\"></div>\n",
+            "<div class=\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\" id=\"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\"></div>\n",
+        );
+
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    collapse_empty_elements: true,
+                    ..Configuration::default()
+                }
+            )?,
+            concat!(
+                "<div></div>\n",
+                "<div class=\"x\" id=\"y\"></div>\n",
+                "<div\n",
+                "  class=\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"\n",
+                "  id=\"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\"\n",
+                "></div>\n",
+            )
+        );
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            concat!(
+                "<div></div>\n",
+                "<div class=\"x\" id=\"y\"></div>\n",
+                "<div class=\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\" id=\"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\">\n",
+                "</div>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_embedded_css() -> anyhow::Result<()> {
+        let input = concat!(
+            "<div>\n",
+            "  <div>\n",
+            "    <style>\n",
+            "@media print {\n",
+            "p { color: red; }\n",
+            "a {\n",
+            "      color: blue;\n",
+            "}\n",
+            "\n",
+            "\n",
+            "}\n",
+            "</style>\n",
+            "  </div>\n",
+            "</div>\n",
+        );
+
+        assert_eq!(format(input, &Configuration::default())?, input);
+
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    format_embedded_css: true,
+                    ..Configuration::default()
+                }
+            )?,
+            concat!(
+                "<div>\n",
+                "  <div>\n",
+                "    <style>\n",
+                "      @media print {\n",
+                "        p { color: red; }\n",
+                "        a {\n",
+                "          color: blue;\n",
+                "        }\n",
+                "\n",
+                "      }\n",
+                "    </style>\n",
+                "  </div>\n",
+                "</div>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reindent_embedded_script() -> anyhow::Result<()> {
+        let input = concat!(
+            "<div>\n",
+            "  <div>\n",
+            "    <div>\n",
+            "      <script>\n",
+            "if (a) {\n",
+            "  const b = `\n",
+            "    c\n",
+            "`;\n",
+            "\n",
+            "\n",
+            "  run(b);\n",
+            "}\n",
+            "</script>\n",
+            "      <script>run();</script>\n",
+            "    </div>\n",
+            "  </div>\n",
+            "</div>\n",
+        );
+
+        assert_eq!(format(input, &Configuration::default())?, input);
+
+        let configuration = Configuration {
+            reindent_embedded_script: true,
+            ..Configuration::default()
+        };
+
+        let expected = concat!(
+            "<div>\n",
+            "  <div>\n",
+            "    <div>\n",
+            "      <script>\n",
+            "        if (a) {\n",
+            "          const b = `\n",
+            "    c\n",
+            "`;\n",
+            "\n",
+            "\n",
+            "          run(b);\n",
+            "        }\n",
+            "      </script>\n",
+            "      <script>run();</script>\n",
+            "    </div>\n",
+            "  </div>\n",
+            "</div>\n",
+        );
+
+        assert_eq!(format(input, &configuration)?, expected);
+        assert_eq!(format(expected, &configuration)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frontmatter() -> anyhow::Result<()> {
+        let expected = concat!(
+            "---\n",
+            "title:   Home\n",
+            "\n",
+            "tags: [a,  b]\n",
+            "---\n",
+            "\n",
+            "<p>Hello</p>\n",
+        );
+
+        assert_eq!(
+            format(
+                "---\ntitle:   Home\n\ntags: [a,  b]\n---\n<p>Hello</p>",
+                &Configuration::default()
+            )?,
+            expected
+        );
+        assert_eq!(format(expected, &Configuration::default())?, expected);
+
+        // Only at the very start.
+        assert_eq!(
+            format("<p>Hello</p>\n---\na\n---", &Configuration::default())?,
+            "<p>Hello</p>\n--- a ---\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interpolation() -> anyhow::Result<()> {
+        let configuration = |interpolation| Configuration {
+            line_width: 20,
+            interpolation,
+            ..Configuration::default()
+        };
+
+        let input = "<p>Hello there {{ user.name }} ${ a + b }</p>";
+
+        assert_eq!(
+            format(input, &configuration(Interpolation::Mustache))?,
+            "<p>\n  Hello there\n  {{ user.name }} ${\n  a + b }\n</p>\n"
+        );
+        assert_eq!(
+            format(input, &configuration(Interpolation::MustacheAndDollar))?,
+            "<p>\n  Hello there\n  {{ user.name }}\n  ${ a + b }\n</p>\n"
+        );
+        assert_eq!(
+            format(input, &configuration(Interpolation::None))?,
+            "<p>\n  Hello there {{\n  user.name }} ${ a\n  + b }\n</p>\n"
+        );
+
+        // Unclosed.
+        assert_eq!(
+            format(
+                "<p>Hello there {{ user.name</p>",
+                &configuration(Interpolation::Mustache)
+            )?,
+            "<p>\n  Hello there {{\n  user.name\n</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_document() -> anyhow::Result<()> {
+        let config = Configuration::default();
+
+        let page = concat!(
+            "<!-- page -->\n",
+            "<!DOCTYPE html>\n",
+            "<html lang=\"en\">\n",
+            "  <head>\n",
+            "    <title>Home</title>\n",
+            "  </head>\n",
+            "  <body>\n",
+            "    <p>Hello</p>\n",
+            "  </body>\n",
+            "</html>\n",
+        );
+        assert_eq!(format_document(page, &config)?, format(page, &config)?);
+        assert_eq!(
+            format_document("<!DOCTYPE html>\n<title>.</title>\n", &config)?,
+            "<!DOCTYPE html>\n<title>.</title>\n"
+        );
+
+        let error = |input| {
+            format_document(input, &config)
+                .unwrap_err()
+                .downcast::<DocumentError>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            error("<!DOCTYPE html>\n<!DOCTYPE html>\n<html></html>\n"),
+            DocumentError::DuplicateDoctype
+        );
+        assert_eq!(
+            error("<p>Hello</p>\n<!DOCTYPE html>\n"),
+            DocumentError::MisplacedDoctype
+        );
+        assert_eq!(
+            error("<!DOCTYPE html>\n<html></html>\n<p>Hello</p>\n"),
+            DocumentError::ContentOutsideRoot
+        );
+        assert_eq!(error("<p>Hello</p>\n"), DocumentError::NotADocument);
+
+        // Still fine as fragments.
+        format("<!DOCTYPE html>\n<!DOCTYPE html>\n", &config)?;
+        format("<p>Hello</p>\n", &config)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_with_ast() -> anyhow::Result<()> {
+        let config = Configuration::default();
+        let input = "<!DOCTYPE html>\n<ul><li>a<li>b</ul>\n\n<p>Hello <b>world</b></p>";
+
+        let (output, nodes) = format_with_ast(input, &config)?;
+
+        assert_eq!(output, format(input, &config)?);
+        assert_eq!(nodes, crate::parse(input)?);
+
+        // Borrowed from `input`.
+        assert_eq!(nodes[1].span(input), Some(16..35));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bom() -> anyhow::Result<()> {
+        let input = "\u{FEFF}<!DOCTYPE html>\n<title>Hello</title>";
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            "\u{FEFF}<!DOCTYPE html>\n<title>Hello</title>\n"
+        );
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    preserve_bom: false,
+                    ..Configuration::default()
+                }
+            )?,
+            "<!DOCTYPE html>\n<title>Hello</title>\n"
+        );
+        assert_eq!(
+            format_document(input, &Configuration::default())?,
+            format(input, &Configuration::default())?
+        );
+
+        let error = format("\u{FEFF}<b></span>", &Configuration::default())
+            .unwrap_err()
+            .downcast::<ParseError>()?;
+        assert_eq!((error.offset(), error.column()), (6, 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_options() -> anyhow::Result<()> {
+        let config = Configuration {
+            parse_options: ParseOptions {
+                extra_void_elements: &["my-void"],
+                extra_raw_text_elements: &["raw"],
+                ..ParseOptions::default()
+            },
+            ..Configuration::default()
+        };
+
+        let input = "<div><my-void></div>\n<raw>\n  a < b  &amp;  c\n</raw>";
+        let expected = "<div>\n  <my-void/>\n</div>\n<raw>\n  a < b  &amp;  c\n</raw>\n";
+
+        assert_eq!(format(input, &config)?, expected);
+        assert_eq!(assert_idempotent(input, &config)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_with_hint() -> anyhow::Result<()> {
+        let config = Configuration::default();
+
+        let hint = |path| FormatHint {
+            path: Some(Path::new(path)),
+        };
+
+        let fragment = "<p>Hello</p>\n";
+
+        assert!(format_with_hint(fragment, &config, hint("src/index.html"))
+            .unwrap_err()
+            .is::<DocumentError>());
+        assert!(format_with_hint(fragment, &config, hint("INDEX.HTM")).is_err());
+
+        for hint in [
+            hint("App.vue"),
+            hint("partial.njk"),
+            hint("html"),
+            FormatHint::default(),
+        ] {
+            assert_eq!(format_with_hint(fragment, &config, hint)?, fragment);
+        }
+
+        let page = "<!DOCTYPE html>\n<title>.</title>\n";
+        assert_eq!(format_with_hint(page, &config, hint("index.html"))?, page);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_element_overrides() -> anyhow::Result<()> {
+        const fn display(display: ElementDisplay) -> ElementOverride {
+            ElementOverride {
+                display: Some(display),
+                attribute_wrap: None,
+            }
+        }
+
+        const fn attribute_wrap(attribute_wrap: AttributeWrap) -> ElementOverride {
+            ElementOverride {
+                display: None,
+                attribute_wrap: Some(attribute_wrap),
+            }
+        }
+
+        let config = Configuration {
+            max_attributes_inline: Some(1),
+            element_overrides: [
+                ("SPAN", display(ElementDisplay::Block)),
+                ("div", display(ElementDisplay::Inline)),
+                ("custom-x", display(ElementDisplay::Preserve)),
+                ("a", attribute_wrap(AttributeWrap::OnePerLine)),
+                ("img", attribute_wrap(AttributeWrap::Fit)),
+            ]
+            .into_iter()
+            .collect(),
+            ..Configuration::default()
+        };
+        let format_both = |input| -> anyhow::Result<_> {
+            let formatted = format(input, &config)?;
+
+            assert_eq!(format(&formatted, &config)?, formatted);
+
+            Ok((formatted, format(input, &Configuration::default())?))
+        };
+
+        assert_eq!(
+            format_both("<p>a <em>b</em> <span>c</span> d</p>")?,
+            (
+                "<p>\n  a <em>b</em>\n  <span>\n    c\n  </span>\n  d\n</p>\n".to_string(),
+                "<p>a <em>b</em> <span>c</span> d</p>\n".to_string()
+            )
+        );
+        assert_eq!(
+            format_both("<section>a <div>b</div> c <p>x</p></section>")?,
+            (
+                "<section>\n  a <div>b</div> c\n  <p>x</p>\n</section>\n".to_string(),
+                "<section>\n  a\n  <div>b</div>\n  c\n  <p>x</p>\n</section>\n".to_string()
+            )
+        );
+        assert_eq!(
+            format_both(
+                "<main><custom-x   a=1 >  keep   <b>this</b> </custom-x><p>  x </p></main>"
+            )?
+            .0,
+            "<main>\n  <custom-x   a=1 >  keep   <b>this</b> </custom-x>\n  <p>x</p>\n</main>\n"
+        );
+        assert_eq!(
+            format_both("<p><a href=x>l</a> <img src=a alt=b> <input type=a name=b></p>")?.0,
+            concat!(
+                "<p>\n",
+                "  <a\n",
+                "    href=\"x\"\n",
+                "  >l</a> <img src=\"a\" alt=\"b\"/>\n",
+                "  <input\n",
+                "    type=\"a\"\n",
+                "    name=\"b\"\n",
+                "  />\n",
+                "</p>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stray_end_tag() -> anyhow::Result<()> {
+        let config = Configuration::default();
+
+        for (input, offset) in [("</div>\n<p>a</p>", 0), ("<p>a</p>\n</div>\n<p>b</p>", 9)] {
+            let error = format(input, &config).unwrap_err();
+            let error = error.downcast_ref::<ParseError>().unwrap();
+
+            assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEndTag);
+            assert_eq!(error.offset(), offset);
+
+            assert!(format_streaming(input, &config).is_err());
+        }
+
+        // The rest of the input is kept as-is instead.
+        let config = Configuration {
+            strict: false,
+            ..config
+        };
+
+        for (input, expected) in [
+            ("</div>\n<p>a</p>", "</div>\n<p>a</p>\n"),
+            (
+                "<div><p>a</p></div>\n</div>\n<p>b</p>\n\n",
+                "<div>\n  <p>a</p>\n</div>\n</div>\n<p>b</p>\n",
+            ),
+            (
+                "<p>a</p>\r\n</div>\r\n<p  >b</p>\r\n",
+                "<p>a</p>\r\n</div>\r\n<p  >b</p>\r\n",
+            ),
+            ("<p>a</p>", "<p>a</p>\n"),
+        ] {
+            let config = Configuration {
+                line_ending: LineEnding::Auto,
+                ..config.clone()
+            };
+
+            assert_eq!(format(input, &config)?, expected);
+            assert_eq!(format_streaming(input, &config)?, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_streaming() -> anyhow::Result<()> {
+        let configurations = [
+            Configuration {
+                max_blank_lines: 1,
+                ..Configuration::default()
+            },
+            Configuration {
+                remove_comments: RemoveComments::RemoveAll,
+                preserve_document_edges: true,
+                line_ending: LineEnding::Crlf,
+                ..Configuration::default()
+            },
+            Configuration {
+                max_blank_lines: 2,
+                remove_comments: RemoveComments::KeepImportant,
+                insert_final_newline: false,
+                whitespace_sensitivity: WhitespaceSensitivity::Strict,
+                ..Configuration::default()
+            },
+        ];
+
+        let mut inputs = vec![
+            String::new(),
+            " \n ".to_string(),
+            "\u{feff}<p>a</p>\r\n".to_string(),
+            "---\ntitle: Home\n---\n\n<p>a</p>".to_string(),
+            " a <b>b</b> ".to_string(),
+            "<!-- a -->\n\n<p>a</p>\n\n<!-- b -->\n\n\n<!-- c -->\n<p>b</p>\n\n<!-- d -->"
+                .to_string(),
+            "<!-- hast-ignore -->\n\n<p  >a</p >\n<p  >b</p >".to_string(),
+            "<!--! important --><!-- removed --><b>a</b> <!-- removed --> b".to_string(),
+        ];
+
+        for entry in std::fs::read_dir(
+            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests"]
+                .into_iter()
+                .collect::<PathBuf>(),
+        )? {
+            let path = entry?.path();
+
+            if path.extension().and_then(OsStr::to_str) == Some("html") {
+                inputs.push(std::fs::read_to_string(&path)?);
+            }
+        }
+
+        for input in &inputs {
+            for configuration in &configurations {
+                assert_eq!(
+                    format_streaming(input, configuration)?,
+                    format(input, configuration)?,
+                    "{input:?}"
+                );
+            }
+        }
+
+        assert!(format_streaming("<p>a</p>\n</div>", &Configuration::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_comments() -> anyhow::Result<()> {
+        let input = concat!(
+            "<!--! MIT License -->\n",
+            "<div>\n",
+            "  <!-- start -->\n",
+            "\n",
+            "  <p>a<!-- inline -->b</p>\n",
+            "\n",
+            "  <!-- between -->\n",
+            "\n",
+            "  <!--[if IE]><p>IE</p><![endif]-->\n",
+            "  <! bogus>\n",
+            "  <!-- hast-ignore -->\n",
+            "  <p   >kept</p>\n",
+            "  <!-- end -->\n",
+            "</div>\n",
+        );
+        let format_with = |remove_comments| {
+            format(
+                input,
+                &Configuration {
+                    remove_comments,
+                    max_blank_lines: 1,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(RemoveComments::Keep)?,
+            input.replace("<!--!", "<!-- !")
+        );
+
+        let removed = concat!(
+            "<div>\n",
+            "  <p>ab</p>\n",
+            "\n",
+            "  <!--[if IE]><p>IE</p><![endif]-->\n",
+            "  <!-- hast-ignore -->\n",
+            "  <p   >kept</p>\n",
+            "</div>\n",
+        );
+
+        assert_eq!(format_with(RemoveComments::RemoveAll)?, removed);
+        assert_eq!(
+            format_with(RemoveComments::KeepImportant)?,
+            ["<!-- ! MIT License -->\n", removed].concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_range() -> anyhow::Result<()> {
+        let config = Configuration {
+            max_blank_lines: 1,
+            ..Configuration::default()
+        };
+        let input = concat!(
+            "<div  class=a>kept</div>\n",
+            "<ul>\n",
+            "    <li>  one  </li>\n",
+            "    <li><b>two</b>   <i>three</i></li>\n",
+            "\n",
+            "\n",
+            "    <li> four </li>\n",
+            "</ul>\n",
+        );
+        let range = |text: &str| {
+            let start = input.find(text).unwrap();
+
+            start..start + text.len()
+        };
+
+        assert_eq!(
+            format_range(input, range("two"), &config)?,
+            input.replace(
+                "<li><b>two</b>   <i>three</i></li>",
+                "<li><b>two</b> <i>three</i></li>"
+            )
+        );
+        // Expanded to the whole `<li>`s, blank lines included.
+        assert_eq!(
+            format_range(input, range("one  </li>\n    <li><b>"), &config)?,
+            input.replace("<li>  one  </li>", "<li>one</li>").replace(
+                "<li><b>two</b>   <i>three</i></li>",
+                "<li><b>two</b> <i>three</i></li>"
+            )
+        );
+        assert_eq!(
+            format_range(input, range("<ul>"), &config)?,
+            concat!(
+                "<div  class=a>kept</div>\n",
+                "<ul>\n",
+                "  <li>one</li>\n",
+                "  <li><b>two</b> <i>three</i></li>\n",
+                "\n",
+                "  <li>four</li>\n",
+                "</ul>\n",
+            )
+        );
+        // Nested nodes are indented like the first one, and lines fit within the line width.
+        assert_eq!(
+            format_range(
+                input,
+                range(" four "),
+                &Configuration {
+                    line_width: 14,
+                    ..config.clone()
+                }
+            )?,
+            input.replace("<li> four </li>", "<li>\n      four\n    </li>")
+        );
+        assert_eq!(
+            format_range(input, 0..0, &config)?,
+            input.replace("<div  class=a>", "<div class=\"a\">")
+        );
+        assert_eq!(
+            format_range(input, input.len()..input.len(), &config)?,
+            input
+        );
+
+        assert!(format_range(input, 0..input.len() + 1, &config).is_err());
+        assert!(format_range("<p>é</p>", 4..5, &config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_range_in_context() -> anyhow::Result<()> {
+        let input = "<svg>\n  <rect   x=\"1\"/>\n  <g>  <circle/></g>\n</svg>\n";
+        let start = input.find("<g>").unwrap();
+
+        assert_eq!(
+            format_range(input, start..start, &Configuration::default())?,
+            "<svg>\n  <rect   x=\"1\"/>\n  <g>\n    <circle/>\n  </g>\n</svg>\n"
+        );
+
+        let config = Configuration {
+            parse_options: ParseOptions {
+                self_closing_tags: true,
+                ..ParseOptions::default()
+            },
+            ..Configuration::default()
+        };
+        let input = "<section   />\n<p>   a</p>\n";
+
+        assert_eq!(
+            format_range(input, 0..5, &config)?,
+            "<section/>\n<p>   a</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preserve_document_edges() -> anyhow::Result<()> {
+        let configuration = |preserve_document_edges| Configuration {
+            preserve_document_edges,
+            insert_final_newline: false,
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(" <span>x</span> ", &configuration(true))?,
+            " <span>x</span> "
+        );
+        assert_eq!(
+            format("\n  Hello   world\n", &configuration(true))?,
+            " Hello world "
+        );
+        assert_eq!(
+            format(" <span>x</span> ", &configuration(false))?,
+            "<span>x</span>"
+        );
+
+        // Not significant next to a block.
+        assert_eq!(
+            format(" <div>x</div>\n", &configuration(true))?,
+            "<div>x</div>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_closing() -> anyhow::Result<()> {
+        assert_eq!(
+            format(
+                "<div/>text</div><svg><circle r=\"1\"/></svg>",
+                &Configuration::default()
+            )?,
+            "<div>text</div>\n<svg>\n  <circle r=\"1\"/>\n</svg>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_wrap() -> anyhow::Result<()> {
+        let input = concat!(
+            "<p>\n",
+            "  See https://example.com/a/very/long/url/that/does/not/fit/on/a/line for\n",
+            "  details,   or ask.\n",
+            "</p>\n",
+        );
+
+        let format_with = |text_wrap| {
+            format(
+                input,
+                &Configuration {
+                    line_width: 40,
+                    text_wrap,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(TextWrap::Reflow)?,
+            concat!(
+                "<p>\n",
+                "  See\n",
+                "  https://example.com/a/very/long/url/that/does/not/fit/on/a/line\n",
+                "  for details, or ask.\n",
+                "</p>\n",
+            )
+        );
+
+        assert_eq!(
+            format_with(TextWrap::Preserve)?,
+            concat!(
+                "<p>\n",
+                "  See https://example.com/a/very/long/url/that/does/not/fit/on/a/line for\n",
+                "  details, or ask.\n",
+                "</p>\n",
+            )
+        );
+
+        assert_eq!(
+            format_with(TextWrap::NoWrap)?,
+            concat!(
+                "<p>\n",
+                "  See https://example.com/a/very/long/url/that/does/not/fit/on/a/line for details, or ask.\n",
+                "</p>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_attributes_inline() -> anyhow::Result<()> {
+        let configuration = Configuration {
+            max_attributes_inline: Some(2),
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(r#"<input type="text" name="a">"#, &configuration)?,
+            "<input type=\"text\" name=\"a\"/>\n"
+        );
+
+        assert_eq!(
+            format(r#"<input type="text" name="a" required>"#, &configuration)?,
+            "<input\n  type=\"text\"\n  name=\"a\"\n  required\n/>\n"
+        );
+
+        assert_eq!(
+            format(r#"<div id="a" class="b" hidden>Hi</div>"#, &configuration)?,
+            "<div\n  id=\"a\"\n  class=\"b\"\n  hidden\n>Hi</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-width")]
+    fn test_unicode_width() -> anyhow::Result<()> {
+        let configuration = Configuration {
+            line_width: 20,
+            ..Configuration::default()
+        };
+
+        // Each word is 6 columns (but 9 bytes) wide.
+        assert_eq!(
+            format("<p>日本語 日本語 日本語 日本語</p>", &configuration)?,
+            "<p>\n  日本語 日本語\n  日本語 日本語\n</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_order() -> anyhow::Result<()> {
+        let input = r#"<div id="a" @click="go" Class="b" :title="t" data-x hidden></div>"#;
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            format!("{input}\n")
+        );
+
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    attribute_order: AttributeOrder::Alphabetical,
+                    ..Configuration::default()
+                }
+            )?,
+            "<div Class=\"b\" data-x hidden id=\"a\" :title=\"t\" @click=\"go\"></div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_grouping() -> anyhow::Result<()> {
+        let input = concat!(
+            "<MyComponent id=\"a\" @click=\"go\" v-if=\"shown\" :title=\"t\" class=\"b\" ",
+            "v-on:input=\"set\" x-data v-bind:key=\"k\"/>",
+        );
+
+        let grouped = |attribute_grouping| {
+            format(
+                input,
+                &Configuration {
+                    attribute_grouping,
+                    line_width: 200,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(grouped(None)?, format!("{input}\n"));
+
+        assert_eq!(
+            grouped(Some([
+                AttributeGroup::Directive,
+                AttributeGroup::Plain,
+                AttributeGroup::Binding,
+                AttributeGroup::Event,
+            ]))?,
+            concat!(
+                "<MyComponent v-if=\"shown\" x-data id=\"a\" class=\"b\" :title=\"t\" ",
+                "v-bind:key=\"k\" @click=\"go\" v-on:input=\"set\"/>\n",
+            )
+        );
+
+        // Unlisted groups go last.
+        assert_eq!(
+            grouped(Some([AttributeGroup::Event; 4]))?,
+            concat!(
+                "<MyComponent @click=\"go\" v-on:input=\"set\" id=\"a\" v-if=\"shown\" ",
+                ":title=\"t\" class=\"b\" x-data v-bind:key=\"k\"/>\n",
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiline_attribute_values() -> anyhow::Result<()> {
+        let input = concat!(
+            "<section>\n",
+            "<div style=\"\n",
+            "      color: red;\n",
+            "\n",
+            "        margin: 0;\n",
+            "    \" title=\"a\n  b\"></div>\n",
+            "</section>\n",
+        );
+
+        let format_with = |multiline_attribute_values| {
+            format(
+                input,
+                &Configuration {
+                    multiline_attribute_values,
+                    ..Configuration::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            format_with(MultilineAttributeValues::Preserve)?,
+            concat!(
+                "<section>\n",
+                "  <div style=\"\n",
+                "      color: red;\n",
+                "\n",
+                "        margin: 0;\n",
+                "    \" title=\"a\n  b\"></div>\n",
+                "</section>\n",
+            )
+        );
+
+        assert_eq!(
+            format_with(MultilineAttributeValues::Collapse)?,
+            concat!(
+                "<section>\n",
+                "  <div style=\" color: red; margin: 0; \" title=\"a b\"></div>\n",
+                "</section>\n",
+            )
+        );
+
+        assert_eq!(
+            format_with(MultilineAttributeValues::Indent)?,
+            concat!(
+                "<section>\n",
+                "  <div\n",
+                "    style=\"\n",
+                "      color: red;\n",
+                "\n",
+                "        margin: 0;\n",
+                "    \"\n",
+                "    title=\"a\n",
+                "      b\"\n",
+                "  >\n",
+                "  </div>\n",
+                "</section>\n",
+            )
+        );
+
+        for multiline_attribute_values in [
+            MultilineAttributeValues::Preserve,
+            MultilineAttributeValues::Collapse,
+            MultilineAttributeValues::Indent,
+        ] {
+            assert_idempotent(
+                input,
+                &Configuration {
+                    multiline_attribute_values,
+                    ..Configuration::default()
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whitespace_sensitivity() -> anyhow::Result<()> {
+        let format_with = |input, whitespace_sensitivity| {
+            let config = Configuration {
+                whitespace_sensitivity,
+                line_width: 30,
+                ..Configuration::default()
+            };
+
+            assert_idempotent(input, &config)?;
+            format(input, &config)
+        };
+
+        let spans = "<p><span>aaaaaa</span> <span>bbbbbb</span><span>cccccc</span></p>";
+        let divs = "<section><div>aaaaaa</div> <div>bbbbbb</div><div>cccccc</div></section>";
+
+        let spans_kept = concat!(
+            "<p>\n",
+            "  <span>aaaaaa</span>\n",
+            "  <span>bbbbbb</span><span>\n",
+            "    cccccc\n",
+            "  </span>\n",
+            "</p>\n",
+        );
+        let divs_dropped = concat!(
+            "<section>\n",
+            "  <div>aaaaaa</div>\n",
+            "  <div>bbbbbb</div>\n",
+            "  <div>cccccc</div>\n",
+            "</section>\n",
+        );
+
+        assert_eq!(format_with(spans, WhitespaceSensitivity::Css)?, spans_kept);
+        assert_eq!(format_with(divs, WhitespaceSensitivity::Css)?, divs_dropped);
+
+        assert_eq!(
+            format_with(spans, WhitespaceSensitivity::Strict)?,
+            spans_kept
+        );
+        assert_eq!(
+            format_with(divs, WhitespaceSensitivity::Strict)?,
+            concat!(
+                "<section>\n",
+                "  <div>aaaaaa</div>\n",
+                "  <div>bbbbbb</div><div>\n",
+                "    cccccc\n",
+                "  </div>\n",
+                "</section>\n",
+            )
+        );
+        assert_eq!(
+            format_with(
+                "<div><div>a</div> <div>b</div></div>",
+                WhitespaceSensitivity::Strict
+            )?,
+            "<div>\n  <div>a</div> <div>b</div>\n</div>\n"
+        );
+
+        assert_eq!(
+            format_with(spans, WhitespaceSensitivity::Ignore)?,
+            concat!(
+                "<p>\n",
+                "  <span>aaaaaa</span>\n",
+                "  <span>bbbbbb</span>\n",
+                "  <span>cccccc</span>\n",
+                "</p>\n",
+            )
+        );
+        assert_eq!(
+            format_with(divs, WhitespaceSensitivity::Ignore)?,
+            divs_dropped
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mixed_block_content() -> anyhow::Result<()> {
+        assert_eq!(
+            format(
+                "<ul>Fruits: <li>Apple</li> <li>Banana</li></ul>",
+                &Configuration::default()
+            )?,
+            "<ul>\n  Fruits:\n  <li>Apple</li>\n  <li>Banana</li>\n</ul>\n"
+        );
+
+        // Inline content only.
+        assert_eq!(
+            format("<p>Some <b>bold</b> text</p>", &Configuration::default())?,
+            "<p>Some <b>bold</b> text</p>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_break_attributes_with_content() -> anyhow::Result<()> {
+        let config = Configuration {
+            break_attributes_with_content: true,
+            ..Configuration::default()
+        };
+
+        let input = r#"<ul id="list" class="items"><li>a</li><li>b</li></ul>"#;
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            "<ul id=\"list\" class=\"items\">\n  <li>a</li>\n  <li>b</li>\n</ul>\n"
+        );
+        assert_eq!(
+            format(input, &config)?,
+            concat!(
+                "<ul\n",
+                "  id=\"list\"\n",
+                "  class=\"items\"\n",
+                ">\n",
+                "  <li>a</li>\n",
+                "  <li>b</li>\n",
+                "</ul>\n",
+            )
+        );
+        assert_idempotent(input, &config)?;
+
+        // Inline content, a single attribute or verbatim content.
+        for input in [
+            "<p id=\"a\" class=\"b\">Some <b>bold</b> text</p>\n",
+            "<ul id=\"list\">\n  <li>a</li>\n</ul>\n",
+            "<pre id=\"a\" class=\"b\"><b>a</b></pre>\n",
+        ] {
+            assert_eq!(format(input, &config)?, input);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_class_tokens() -> anyhow::Result<()> {
+        let configuration = Configuration {
+            sort_class_tokens: true,
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(
+                r#"<div class="b a  b c" title="b a"></div>"#,
+                &configuration
+            )?,
+            "<div class=\"a b c\" title=\"b a\"></div>\n"
+        );
+
+        assert_eq!(
+            format(r#"<div class="b {{ a }}"></div>"#, &configuration)?,
+            "<div class=\"b {{ a }}\"></div>\n"
+        );
+
+        assert_eq!(
+            format(r#"<div class="b a b"></div>"#, &Configuration::default())?,
+            "<div class=\"b a b\"></div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bogus_comment() -> anyhow::Result<()> {
+        assert_eq!(
+            format(
+                "<div>\n<! foo>\n</ >\n<? x ></div>",
+                &Configuration::default()
+            )?,
+            "<div>\n  <! foo>\n  </ >\n  <? x >\n</div>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_boolean_attribute_style() -> anyhow::Result<()> {
+        for (boolean_attribute_style, expected) in [
+            (BooleanAttributeStyle::Minimal, "<input required/>\n"),
+            (BooleanAttributeStyle::Empty, "<input required=\"\"/>\n"),
+            (
+                BooleanAttributeStyle::Explicit,
+                "<input required=\"required\"/>\n",
+            ),
+        ] {
+            assert_eq!(
+                format(
+                    "<input required>",
+                    &Configuration {
+                        boolean_attribute_style,
+                        ..Configuration::default()
+                    }
+                )?,
+                expected
+            );
+        }
+
+        // Values are kept.
+        assert_eq!(
+            format(
+                "<input required=''>",
+                &Configuration {
+                    boolean_attribute_style: BooleanAttributeStyle::Explicit,
+                    ..Configuration::default()
+                }
+            )?,
+            "<input required=\"\"/>\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiline_comment_indentation() -> anyhow::Result<()> {
+        let expected = concat!(
+            "<div>\n",
+            "  <div>\n",
+            "    <!--\n",
+            "      a\n",
+            "        b\n",
+            "\n",
+            "      c\n",
+            "    -->\n",
+            "  </div>\n",
+            "</div>\n",
+        );
+
+        // E.g. from the source being indented differently, or not at all.
+        for lines in [
+            "a\n  b\n\nc",
+            "            a\n              b\n    \n            c",
+        ] {
+            let input = format!("<div><div><!--\n{lines}\n--></div></div>");
+
+            assert_eq!(format(&input, &Configuration::default())?, expected);
+        }
+
+        assert_eq!(
+            format(
+                "<div><div><!--\n\t\ta\n\t\t  b\n\n\t\tc\n--></div></div>",
+                &Configuration::default()
+            )?,
+            expected
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comment_dashes() -> anyhow::Result<()> {
+        assert_eq!(
+            format(
+                "<p>a<!-- -- -->b<!-->c<!--->d</p>",
+                &Configuration::default()
+            )?,
+            "<p>a<!-- -- -->b<!---->c<!---->d</p>\n"
+        );
+
+        assert_eq!(
+            format("<!--\n\n  a --\n  -- b\n\n-->", &Configuration::default())?,
+            "<!--\n  a --\n  -- b\n-->\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reflow_comments() -> anyhow::Result<()> {
+        let input = concat!(
+            "<div>\n",
+            "  <!--  short  comment  -->\n",
+            "  <!-- A comment that is way longer than the configured width of forty columns -->\n",
+            "  <!--! Important comment that is longer than the configured width, kept as-is -->\n",
+            "  <!--[if IE]><p>A conditional comment longer than the configured width</p><![endif]-->\n",
+            "</div>\n",
+        );
+
+        let config = Configuration {
+            line_width: 40,
+            reflow_comments: true,
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            format(input, &config)?,
+            concat!(
+                "<div>\n",
+                "  <!-- short  comment -->\n",
+                "  <!--\n",
+                "    A comment that is way longer than\n",
+                "    the configured width of forty\n",
+                "    columns\n",
+                "  -->\n",
+                "  <!--\n",
+                "    ! Important comment that is longer than the configured width, kept as-is\n",
+                "  -->\n",
+                "  <!--[if IE]><p>A conditional comment longer than the configured width</p><![endif]-->\n",
+                "</div>\n",
+            )
+        );
+
+        assert_idempotent(input, &config)?;
+
+        // Otherwise only put on its own lines.
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    reflow_comments: false,
+                    ..config.clone()
+                }
+            )?,
+            format(input, &config)?.replace(
+                "    A comment that is way longer than\n    the configured width of forty\n    columns\n",
+                "    A comment that is way longer than the configured width of forty columns\n"
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preserve_comment_whitespace() -> anyhow::Result<()> {
+        let input = "<!--  keep  spaces  -->\n<!--TODO: fix-->\n<!--   -->\n";
+
+        assert_eq!(
+            format(input, &Configuration::default())?,
+            "<!-- keep  spaces -->\n<!-- TODO: fix -->\n<!---->\n"
+        );
+
+        assert_eq!(
+            format(
+                input,
+                &Configuration {
+                    preserve_comment_whitespace: true,
+                    ..Configuration::default()
+                }
+            )?,
+            "<!--  keep  spaces  -->\n<!--TODO: fix-->\n<!---->\n"
+        );
+
+        Ok(())
     }
 }
@@ -1,6 +1,41 @@
-pub use crate::configuration::Configuration;
-pub use crate::format::format;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-mod ast;
+extern crate alloc;
+
+pub use crate::ast::{to_html, to_html_with_options};
+#[cfg(feature = "std")]
+pub use crate::configuration::{
+    AttributeCase, AttributeGroup, AttributeOrder, AttributeWrap, BooleanAttributeStyle,
+    Configuration, ConfigurationBuilder, ConfigurationError, DuplicateAttributes, ElementDisplay,
+    ElementOverride, ElementOverrides, IndentStyle, Interpolation, LineEnding, MinifyConfig,
+    MultilineAttributeValues, QuoteStyle, RemoveComments, TagCase, TextWrap, VoidSelfClose,
+    WhitespaceSensitivity,
+};
+#[cfg(feature = "std")]
+pub use crate::format::{
+    assert_idempotent, check, format, format_document, format_range, format_streaming,
+    format_with_ast, format_with_hint, DocumentError, FormatHint,
+};
+#[cfg(feature = "std")]
+pub use crate::minify::minify;
+pub use crate::parse::{
+    parse, parse_iter, parse_recover, parse_recover_with_options, parse_with_options, NodeIter,
+    ParseError, ParseErrorKind, ParseOptions,
+};
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+pub mod ast;
+pub mod builder;
+#[cfg(feature = "std")]
 mod configuration;
+mod escape;
+#[cfg(feature = "std")]
 mod format;
+#[cfg(feature = "std")]
+mod minify;
+mod parse;
+pub mod select;
+pub mod visit;
+#[cfg(feature = "wasm")]
+pub mod wasm;
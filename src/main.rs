@@ -0,0 +1,174 @@
+use std::{
+    io::{self, Read, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::{anyhow, bail, Context};
+use hast::{format, format_with_hint, Configuration, FormatHint, ParseError};
+use similar::TextDiff;
+
+const USAGE: &str = "\
+Usage: hast [OPTIONS] [FILE]
+
+Format an HTML document, read from FILE or from stdin if omitted.
+
+Options:
+    --line-width <WIDTH>      Maximum line width [default: 80]
+    --indent-width <WIDTH>    Number of spaces per indentation level [default: 2]
+    --stdin-filepath <PATH>   Path of the file read from stdin, formatted as a whole document
+                              if an HTML file (e.g. `.html`) and as a fragment otherwise
+    --write                   Overwrite FILE instead of printing to stdout
+    --check                   Print a diff and exit with 1 if the input isn't formatted
+    -h, --help                Print this message";
+
+struct Args {
+    config: Configuration,
+    path: Option<PathBuf>,
+    stdin_path: Option<PathBuf>,
+    write: bool,
+    check: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<Args>> {
+    let mut config = Configuration::default();
+    let mut path = None;
+    let mut stdin_path = None;
+    let mut write = false;
+    let mut check = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--line-width" => {
+                config.line_width = args
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for `--line-width`"))?
+                    .parse()
+                    .context("invalid value for `--line-width`")?;
+            }
+            "--indent-width" => {
+                config.indent_width = args
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for `--indent-width`"))?
+                    .parse()
+                    .context("invalid value for `--indent-width`")?;
+            }
+            "--stdin-filepath" => {
+                stdin_path =
+                    Some(PathBuf::from(args.next().ok_or_else(|| {
+                        anyhow!("missing value for `--stdin-filepath`")
+                    })?));
+            }
+            "--write" => write = true,
+            "--check" => check = true,
+            flag if flag.starts_with('-') && flag != "-" => bail!("unknown option `{flag}`"),
+            _ if path.is_some() => bail!("unexpected argument `{arg}`"),
+            "-" => {}
+            _ => path = Some(PathBuf::from(arg)),
+        }
+    }
+
+    if write && path.is_none() {
+        bail!("`--write` requires a file");
+    }
+
+    if stdin_path.is_some() && path.is_some() {
+        bail!("`--stdin-filepath` requires reading from stdin");
+    }
+
+    if write && check {
+        bail!("`--write` and `--check` are mutually exclusive");
+    }
+
+    Ok(Some(Args {
+        config,
+        path,
+        stdin_path,
+        write,
+        check,
+    }))
+}
+
+/// Returns whether the input was already formatted, always `true` unless checking.
+fn run(args: &Args) -> anyhow::Result<bool> {
+    let input = match &args.path {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?,
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("failed to read stdin")?;
+            input
+        }
+    };
+
+    let output = match &args.stdin_path {
+        Some(path) => format_with_hint(&input, &args.config, FormatHint { path: Some(path) })?,
+        None => format(&input, &args.config)?,
+    };
+
+    if args.check {
+        if output == input {
+            return Ok(true);
+        }
+
+        let name = match args.path.as_ref().or(args.stdin_path.as_ref()) {
+            Some(path) => path.display().to_string(),
+            None => String::from("<stdin>"),
+        };
+
+        let diff = TextDiff::from_lines(&input, &output)
+            .unified_diff()
+            .header(&name, &name)
+            .to_string();
+
+        io::stdout()
+            .write_all(diff.as_bytes())
+            .context("failed to write stdout")?;
+
+        return Ok(false);
+    }
+
+    match &args.path {
+        Some(path) if args.write => std::fs::write(path, output)
+            .with_context(|| format!("failed to write `{}`", path.display()))?,
+        _ => io::stdout()
+            .write_all(output.as_bytes())
+            .context("failed to write stdout")?,
+    }
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("error: {error:#}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            match (
+                error.downcast_ref::<ParseError>(),
+                args.path.as_ref().or(args.stdin_path.as_ref()),
+            ) {
+                (Some(error), Some(path)) => eprintln!("error: {}: {error}", path.display()),
+                (Some(error), None) => eprintln!("error: <stdin>: {error}"),
+                (None, _) => eprintln!("error: {error:#}"),
+            }
+
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt::Write;
+
+use crate::{
+    ast::{
+        cdata::CData,
+        element::{
+            attribute::{Attribute, AttributeValue},
+            is_escapable_raw_text_element, is_preformatted_element, is_raw_text_element,
+            is_void_element, Element,
+        },
+        processing_instruction::ProcessingInstruction,
+        Node,
+    },
+    escape::{escape_attribute_value, escape_text},
+    MinifyConfig, ParseError,
+};
+
+/// Minify the given input according to the configuration.
+///
+/// # Errors
+/// Will return an error if parsing / printing fails.
+pub fn minify(input: &str, config: &MinifyConfig) -> anyhow::Result<String> {
+    let (_, nodes) = Node::parse_many(input).map_err(|error| ParseError::from_nom(input, error))?;
+
+    let mut buffer = String::new();
+
+    for node in &nodes {
+        minify_node(node, config, false, &mut buffer)?;
+    }
+
+    Ok(buffer)
+}
+
+fn minify_node(
+    node: &Node,
+    config: &MinifyConfig,
+    preformatted: bool,
+    buffer: &mut String,
+) -> std::fmt::Result {
+    match node {
+        Node::BlankLines(_) => Ok(()),
+        Node::Whitespace => buffer.write_char(' '),
+        Node::BogusComment(bogus_comment) => {
+            if !config.remove_comments {
+                write!(buffer, "{bogus_comment}")?;
+            }
+
+            Ok(())
+        }
+        Node::CData(CData(cdata)) => write!(buffer, "<![CDATA[{cdata}]]>"),
+        Node::Comment(comment) => {
+            if !config.remove_comments || comment.is_conditional() {
+                write!(buffer, "{comment}")?;
+            }
+
+            Ok(())
+        }
+        Node::Doctype(doctype) => write!(buffer, "{doctype}"),
+        Node::Element(element) => minify_element(element, config, preformatted, buffer),
+        Node::Frontmatter(frontmatter) => write!(buffer, "{frontmatter}"),
+        Node::ProcessingInstruction(ProcessingInstruction(processing_instruction)) => {
+            write!(buffer, "<?{processing_instruction}?>")
+        }
+        Node::Text(text) if preformatted => buffer.write_str(&escape_text(text)),
+        Node::Text(text) => {
+            // Collapse runs of whitespace into a single space.
+            for (index, word) in text.split_ascii_whitespace().enumerate() {
+                if index != 0 {
+                    buffer.write_char(' ')?;
+                }
+
+                buffer.write_str(&escape_text(word))?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn minify_element(
+    element: &Element,
+    config: &MinifyConfig,
+    preformatted: bool,
+    buffer: &mut String,
+) -> std::fmt::Result {
+    let (name, attributes, content) = match element {
+        Element::Void { name, attributes } => (name.as_ref(), attributes, None),
+        Element::Normal {
+            name,
+            attributes,
+            content,
+        } => (name.as_ref(), attributes, Some(content)),
+    };
+
+    write!(buffer, "<{name}")?;
+
+    for Attribute { name, value } in attributes {
+        write!(buffer, " {name}")?;
+
+        if let Some(AttributeValue { text, .. }) = value {
+            // Prefer double quotes, unless the value only contains those.
+            if text.contains('"') && !text.contains('\'') {
+                write!(buffer, "='{}'", escape_attribute_value(text, Some('\'')))?;
+            } else {
+                write!(buffer, "=\"{}\"", escape_attribute_value(text, Some('"')))?;
+            }
+        }
+    }
+
+    if let Some(content) = content {
+        buffer.write_char('>')?;
+
+        // Raw text isn't escaped, escapable raw text is kept as-is too.
+        if is_raw_text_element(name) || is_escapable_raw_text_element(name) {
+            for node in content {
+                match node {
+                    Node::Text(text) => buffer.write_str(text)?,
+                    node => minify_node(node, config, true, buffer)?,
+                }
+            }
+
+            return write!(buffer, "</{name}>");
+        }
+
+        let preformatted = preformatted || is_preformatted_element(name);
+
+        for node in content {
+            minify_node(node, config, preformatted, buffer)?;
+        }
+
+        write!(buffer, "</{name}>")
+    } else if is_void_element(name) {
+        buffer.write_char('>')
+    } else {
+        // Author self-closed element (e.g. a component), it can't be written as a start tag only.
+        buffer.write_str("/>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{minify, parse, MinifyConfig};
+
+    #[test]
+    fn test_minify() -> anyhow::Result<()> {
+        let input = concat!(
+            "<!DOCTYPE html>\n",
+            "<html lang=\"en\">\n",
+            "  <head>\n",
+            "    <meta charset=\"utf-8\">\n",
+            "    <title>Title</title>\n",
+            "    <style>\n",
+            "      a > b { color: red; }\n",
+            "    </style>\n",
+            "  </head>\n",
+            "  <body>\n",
+            "    <!-- comment -->\n",
+            "    <div id=\"app\" hidden>\n",
+            "      <MyComponent :attr='say(\"hi\")'/>\n",
+            "      <p>Hello world</p>\n",
+            "    </div>\n",
+            "  </body>\n",
+            "</html>\n",
+        );
+
+        let minified = minify(input, &MinifyConfig::default())?;
+
+        assert_eq!(
+            minified,
+            concat!(
+                "<!DOCTYPE html>",
+                "<html lang=\"en\">",
+                "<head>",
+                "<meta charset=\"utf-8\">",
+                "<title>Title</title>",
+                "<style>\n",
+                "      a > b { color: red; }\n",
+                "    </style>",
+                "</head>",
+                "<body>",
+                "<!-- comment -->",
+                "<div id=\"app\" hidden>",
+                "<MyComponent :attr='say(\"hi\")'/>",
+                "<p>Hello world</p>",
+                "</div>",
+                "</body>",
+                "</html>",
+            )
+        );
+
+        assert_eq!(parse(&minified)?, parse(input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minify_whitespace() -> anyhow::Result<()> {
+        assert_eq!(
+            minify("<p>\n  Hello\n  world\n</p>", &MinifyConfig::default())?,
+            "<p>Hello world</p>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minify_remove_comments() -> anyhow::Result<()> {
+        let config = MinifyConfig {
+            remove_comments: true,
+        };
+
+        assert_eq!(
+            minify(
+                "<!-- comment --><!--[if IE]><p>IE</p><![endif]--><p>Hi</p>",
+                &config
+            )?,
+            "<!--[if IE]><p>IE</p><![endif]--><p>Hi</p>"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,835 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
+use crate::ast::{
+    element::{is_inline_element, is_raw_text_element, is_void_element, Element},
+    error::Error,
+    frontmatter::Frontmatter,
+    Node,
+};
+#[cfg(feature = "std")]
+use crate::{
+    ast::util::offset_in,
+    configuration::DuplicateAttributes,
+    visit::{walk_element_mut, walk_nodes_mut, VisitorMut},
+};
+
+/// Byte order mark, skipped at the start of the input (error positions still count its bytes
+/// in [`ParseError::offset`], not in [`ParseError::column`]).
+pub(crate) const BOM: char = '\u{FEFF}';
+
+/// `input` without its leading [`BOM`], if any.
+pub(crate) fn strip_bom(input: &str) -> &str {
+    input.strip_prefix(BOM).unwrap_or(input)
+}
+
+/// Parse the given input into a list of nodes, skipping a leading byte order mark.
+///
+/// The returned nodes borrow from `input`, which must therefore outlive them.
+///
+/// # Errors
+/// Will return an error if parsing fails or if some input is left unparsed (e.g. a stray end tag).
+pub fn parse(input: &str) -> Result<Vec<Node<'_>>, ParseError> {
+    parse_with_options(input, &ParseOptions::default())
+}
+
+/// See [`parse`] and [`ParseOptions`].
+///
+/// # Errors
+/// See [`parse`].
+pub fn parse_with_options<'i>(
+    input: &'i str,
+    options: &ParseOptions<'_>,
+) -> Result<Vec<Node<'i>>, ParseError> {
+    let (remaining, nodes) = Node::parse_many_with_options(strip_bom(input), options)
+        .map_err(|error| ParseError::from_nom(input, error))?;
+
+    if remaining.trim().is_empty() {
+        Ok(nodes)
+    } else {
+        Err(ParseError::new(
+            input,
+            remaining,
+            ParseErrorKind::UnexpectedEndTag,
+        ))
+    }
+}
+
+/// Options of the parser, the defaults follow the HTML spec.
+///
+/// Element names are compared case-insensitively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions<'o> {
+    /// Also void elements (e.g. a web component's `my-icon`), i.e. without content nor end tag.
+    pub extra_void_elements: &'o [&'o str],
+    /// Also raw text elements (e.g. a template engine's `raw`), i.e. whose content is kept
+    /// verbatim up to their end tag.
+    ///
+    /// Only known when parsing and formatting (with [`Configuration::parse_options`]), not when
+    /// writing nodes back to HTML (e.g. with [`to_html`](crate::ast::to_html)) or minifying, which
+    /// would then escape their content.
+    ///
+    /// [`Configuration::parse_options`]: crate::Configuration::parse_options
+    pub extra_raw_text_elements: &'o [&'o str],
+    /// Also inline elements (e.g. a web component's `my-link`), i.e. the whitespace between them
+    /// and text or other inline elements is kept as [`Node::Whitespace`].
+    pub extra_inline_elements: &'o [&'o str],
+    /// Keep the whitespace separating any siblings (not only inline-level ones) as
+    /// [`Node::Whitespace`], i.e. consider every element inline. Set by [`crate::format`] from
+    /// [`Configuration::whitespace_sensitivity`](crate::Configuration::whitespace_sensitivity).
+    pub strict_whitespace: bool,
+    /// Let any element be self-closed (e.g. `<div/>`, see [`Element::is_self_closed`]), as in Vue
+    /// or JSX templates. Otherwise only SVG, MathML and component (e.g. `<MyComponent/>`) elements
+    /// can be, the `/` of others is ignored as browsers do (e.g. `<div/>` is `<div>`).
+    pub self_closing_tags: bool,
+    /// Elements nested deeper fail with [`ParseErrorKind::MaxDepthExceeded`] instead of
+    /// overflowing the stack, defaults to [`MAX_DEPTH`](crate::ast::MAX_DEPTH).
+    pub max_depth: usize,
+}
+
+impl Default for ParseOptions<'_> {
+    fn default() -> Self {
+        Self {
+            extra_void_elements: &[],
+            extra_raw_text_elements: &[],
+            extra_inline_elements: &[],
+            strict_whitespace: false,
+            self_closing_tags: false,
+            max_depth: crate::ast::MAX_DEPTH,
+        }
+    }
+}
+
+impl ParseOptions<'_> {
+    pub(crate) fn is_void_element(&self, name: &str) -> bool {
+        is_void_element(name)
+            || self
+                .extra_void_elements
+                .iter()
+                .any(|extra| extra.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn is_inline_element(&self, name: &str) -> bool {
+        is_inline_element(name)
+            || self
+                .extra_inline_elements
+                .iter()
+                .any(|extra| extra.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn is_raw_text_element(&self, name: &str) -> bool {
+        is_raw_text_element(name)
+            || self
+                .extra_raw_text_elements
+                .iter()
+                .any(|extra| extra.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Parse the given input into a best-effort list of nodes, along with every error encountered.
+///
+/// Unlike [`parse`], errors don't abort parsing: stray end tags are skipped and the
+/// construct that failed to parse is kept as text (up to the next `<`), before resuming.
+///
+/// Each failure is reported once: an end tag that doesn't match the element it closes is only
+/// reported as such, not again as a stray end tag when it's skipped.
+pub fn parse_recover(input: &str) -> (Vec<Node<'_>>, Vec<ParseError>) {
+    parse_recover_with_options(input, &ParseOptions::default())
+}
+
+/// See [`parse_recover`] and [`ParseOptions`].
+pub fn parse_recover_with_options<'i>(
+    input: &'i str,
+    options: &ParseOptions<'_>,
+) -> (Vec<Node<'i>>, Vec<ParseError>) {
+    let mut nodes = Vec::new();
+    let mut errors: Vec<ParseError> = Vec::new();
+
+    let mut remaining = match Frontmatter::parse(strip_bom(input)) {
+        Ok((rest, frontmatter)) => {
+            nodes.push(frontmatter.into());
+            rest.trim_start()
+        }
+        Err(_) => strip_bom(input).trim_start(),
+    };
+
+    while !remaining.is_empty() {
+        let rest = if let Ok((rest, _)) = Element::parse_end_tag(remaining) {
+            let error = ParseError::new(input, remaining, ParseErrorKind::UnexpectedEndTag);
+            // The end tag of an element which failed to parse, already reported as mismatched.
+            if !errors.iter().any(|other| other.offset() == error.offset()) {
+                errors.push(error);
+            }
+
+            rest
+        } else {
+            match Node::parse_next(remaining, options, &mut nodes) {
+                Ok((rest, ())) => rest,
+                Err(error) => {
+                    let error = ParseError::from_nom(input, error);
+
+                    // Retrying the nested constructs may run into the same error.
+                    if !errors.contains(&error) {
+                        errors.push(error);
+                    }
+
+                    let end = remaining[1..]
+                        .find('<')
+                        .map_or(remaining.len(), |index| index + 1);
+
+                    nodes.push(Node::Text(remaining[..end].trim_end().into()));
+
+                    &remaining[end..]
+                }
+            }
+        };
+
+        remaining = rest.trim_start();
+    }
+
+    (nodes, errors)
+}
+
+/// Lazily parse the given input into top-level nodes, see [`parse`].
+pub fn parse_iter(input: &str) -> NodeIter<'_> {
+    NodeIter::new(input)
+}
+
+/// Iterator over top-level nodes, parsed one at a time (each along with its descendants).
+///
+/// After an error, no more nodes are yielded.
+#[derive(Debug, Clone)]
+pub struct NodeIter<'a> {
+    input: &'a str,
+    remaining: &'a str,
+    pending: alloc::vec::IntoIter<Node<'a>>,
+    done: bool,
+    options: ParseOptions<'a>,
+}
+
+impl<'a> NodeIter<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with_options(input, &ParseOptions::default())
+    }
+
+    /// See [`parse_with_options`].
+    pub fn with_options(input: &'a str, options: &ParseOptions<'a>) -> Self {
+        let (remaining, pending) = match Frontmatter::parse(strip_bom(input)) {
+            Ok((rest, frontmatter)) => (rest, vec![frontmatter.into()]),
+            Err(_) => (strip_bom(input), Vec::new()),
+        };
+
+        Self {
+            input,
+            remaining: remaining.trim_start(),
+            pending: pending.into_iter(),
+            done: false,
+            options: *options,
+        }
+    }
+}
+
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = Result<Node<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(node) = self.pending.next() {
+            return Some(Ok(node));
+        }
+
+        if self.done || self.remaining.is_empty() {
+            return None;
+        }
+
+        if Element::parse_end_tag(self.remaining).is_ok() {
+            self.done = true;
+
+            return Some(Err(ParseError::new(
+                self.input,
+                self.remaining,
+                ParseErrorKind::UnexpectedEndTag,
+            )));
+        }
+
+        let mut buffer = Vec::new();
+
+        match Node::parse_next(self.remaining, &self.options, &mut buffer) {
+            Ok((remaining, ())) => {
+                self.remaining = remaining;
+                self.pending = buffer.into_iter();
+                self.pending.next().map(Ok)
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(ParseError::from_nom(self.input, error)))
+            }
+        }
+    }
+}
+
+/// Apply `policy` to attributes repeated (case-insensitively) on the same element.
+///
+/// # Errors
+/// Will return an error located at the first repeated attribute when `policy` is
+/// [`DuplicateAttributes::Error`].
+#[cfg(feature = "std")]
+pub(crate) fn check_duplicate_attributes(
+    input: &str,
+    nodes: &mut [Node<'_>],
+    policy: DuplicateAttributes,
+) -> Result<(), ParseError> {
+    struct Check<'i> {
+        input: &'i str,
+        policy: DuplicateAttributes,
+        error: Option<ParseError>,
+    }
+
+    impl<'a> VisitorMut<'a> for Check<'_> {
+        fn visit_element_mut(&mut self, element: &mut Element<'a>) {
+            if self.error.is_some() {
+                return;
+            }
+
+            let (Element::Normal { attributes, .. } | Element::Void { attributes, .. }) = element;
+
+            let mut index = 0;
+
+            while index < attributes.len() {
+                let name = &attributes[index].name;
+
+                if !attributes[..index]
+                    .iter()
+                    .any(|attribute| attribute.name.eq_ignore_ascii_case(name))
+                {
+                    index += 1;
+                    continue;
+                }
+
+                match self.policy {
+                    DuplicateAttributes::Error => {
+                        // Attribute names are slices of the input, unless owned.
+                        let offset = offset_in(self.input, name).unwrap_or(0);
+
+                        self.error = Some(ParseError::new(
+                            self.input,
+                            &self.input[offset..],
+                            ParseErrorKind::DuplicateAttribute {
+                                name: name.to_string(),
+                            },
+                        ));
+
+                        return;
+                    }
+                    DuplicateAttributes::KeepFirst => {
+                        attributes.remove(index);
+                    }
+                }
+            }
+
+            walk_element_mut(self, element);
+        }
+    }
+
+    let mut check = Check {
+        input,
+        policy,
+        error: None,
+    };
+
+    walk_nodes_mut(&mut check, nodes);
+
+    check.error.map_or(Ok(()), Err)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input ended in the middle of a construct (e.g. a missing end tag).
+    UnexpectedEof,
+    /// An element was closed by the end tag of another element.
+    MismatchedEndTag {
+        /// Name of the element being closed.
+        expected: String,
+        /// Name found in the end tag.
+        found: String,
+    },
+    /// An end tag that doesn't close any element.
+    UnexpectedEndTag,
+    /// An attribute repeated (case-insensitively) on the same element.
+    DuplicateAttribute {
+        /// Name of the repeated attribute, as found in the source.
+        name: String,
+    },
+    /// An `=` not followed by a value (e.g. `<div class=>`).
+    MissingAttributeValue {
+        /// Name of the attribute, as found in the source.
+        name: String,
+    },
+    /// Elements nested more than [`ParseOptions::max_depth`] levels deep.
+    MaxDepthExceeded,
+    InvalidSyntax,
+}
+
+impl ParseError {
+    /// `remaining` must be a suffix of `input`.
+    pub(crate) fn new(input: &str, remaining: &str, kind: ParseErrorKind) -> Self {
+        let offset = input.len() - remaining.len();
+        let before = &input[..offset];
+
+        Self {
+            kind,
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before
+                .rfind('\n')
+                .map_or(strip_bom(before), |index| &before[index + 1..])
+                .chars()
+                .count()
+                + 1,
+        }
+    }
+
+    pub(crate) fn from_nom(input: &str, error: nom::Err<Error>) -> Self {
+        match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => {
+                Self::new(input, error.input, error.kind)
+            }
+            nom::Err::Incomplete(_) => Self::new(input, "", ParseErrorKind::UnexpectedEof),
+        }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset (in the original input) at which parsing failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// 1-based line at which parsing failed.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// 1-based column (in characters) at which parsing failed.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEof => f.write_str("unexpected end of input"),
+            ParseErrorKind::MismatchedEndTag { expected, found } => {
+                write!(f, "expected `</{expected}>` but found `</{found}>`")
+            }
+            ParseErrorKind::UnexpectedEndTag => f.write_str("unexpected end tag"),
+            ParseErrorKind::DuplicateAttribute { name } => {
+                write!(f, "duplicate attribute `{name}`")
+            }
+            ParseErrorKind::MissingAttributeValue { name } => {
+                write!(f, "missing value for attribute `{name}`")
+            }
+            ParseErrorKind::MaxDepthExceeded => f.write_str("elements nested too deep"),
+            ParseErrorKind::InvalidSyntax => f.write_str("invalid syntax"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsStr, path::PathBuf};
+
+    use crate::ast::{
+        comment::Comment,
+        element::{
+            attribute::{Attribute, AttributeValue, Quote},
+            Element,
+        },
+        Node, MAX_DEPTH,
+    };
+
+    #[cfg(feature = "std")]
+    use super::check_duplicate_attributes;
+    use super::{
+        parse, parse_iter, parse_recover, parse_recover_with_options, parse_with_options,
+        ParseErrorKind, ParseOptions,
+    };
+    #[cfg(feature = "std")]
+    use crate::configuration::DuplicateAttributes;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("<!-- comment --><p>Hello</p>"),
+            Ok(vec![
+                Node::Comment(Comment(" comment ".into())),
+                Node::Element(Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("Hello".into())],
+                }),
+            ])
+        );
+
+        assert_eq!(parse(" \n "), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        let options = ParseOptions {
+            extra_void_elements: &["my-void"],
+            extra_raw_text_elements: &["RAW"],
+            ..ParseOptions::default()
+        };
+        let input = "<p><My-Void id=a>text</p><raw><b>{{ x }}</raw>";
+
+        assert_eq!(
+            parse_with_options(input, &options),
+            Ok(vec![
+                Node::Element(Element::Normal {
+                    name: "p".into(),
+                    attributes: vec![],
+                    content: vec![
+                        Node::Element(Element::Void {
+                            name: "My-Void".into(),
+                            attributes: vec![Attribute {
+                                name: "id".into(),
+                                value: Some(AttributeValue {
+                                    text: "a".into(),
+                                    quote: Quote::Unquoted,
+                                }),
+                            }],
+                        }),
+                        Node::Text("text".into()),
+                    ],
+                }),
+                Node::Element(Element::Normal {
+                    name: "raw".into(),
+                    attributes: vec![],
+                    content: vec![Node::Text("<b>{{ x }}".into())],
+                }),
+            ])
+        );
+
+        assert!(parse(input).is_err());
+    }
+
+    #[test]
+    fn test_parse_default_options() -> anyhow::Result<()> {
+        let mut inputs = vec![
+            "<p>a</p>".to_string(),
+            "<ul><li>a<li>b</ul>".to_string(),
+            "<svg><rect/><foreignObject><div/>x</div></foreignObject></svg>".to_string(),
+            "<pre>\n  a <b>b</b>\n</pre>".to_string(),
+            "<p>a</div>".to_string(),
+        ];
+
+        for entry in std::fs::read_dir(
+            [&std::env::var("CARGO_MANIFEST_DIR")?, "tests"]
+                .into_iter()
+                .collect::<PathBuf>(),
+        )? {
+            let path = entry?.path();
+
+            if path.extension().and_then(OsStr::to_str) == Some("html") {
+                inputs.push(std::fs::read_to_string(&path)?);
+            }
+        }
+
+        for input in &inputs {
+            assert_eq!(
+                parse_with_options(input, &ParseOptions::default()),
+                parse(input),
+                "{input}"
+            );
+            assert_eq!(
+                Node::parse_many_with_options(input, &ParseOptions::default()),
+                Node::parse_many(input),
+                "{input}"
+            );
+            assert_eq!(
+                Element::parse_with_options(input, &ParseOptions::default()),
+                Element::parse(input),
+                "{input}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bom() {
+        let input = "\u{FEFF}<!DOCTYPE html>\n<p>Hello</p>";
+
+        assert_eq!(parse(input), parse(&input[3..]));
+        assert_eq!(parse(input).unwrap()[1].span(input), Some(19..31));
+        assert_eq!(parse_recover(input), (parse(input).unwrap(), vec![]));
+        assert_eq!(
+            parse_iter(input).collect::<Result<Vec<_>, _>>(),
+            parse(input)
+        );
+
+        let frontmatter = "\u{FEFF}---\ntitle: Home\n---\n<p>";
+        assert_eq!(
+            parse(frontmatter).unwrap()[0].span(frontmatter),
+            Some(3..23)
+        );
+
+        // The BOM isn't a column, but still bytes.
+        let error = parse("\u{FEFF}</p>").unwrap_err();
+        assert_eq!((error.offset(), error.line(), error.column()), (3, 1, 1));
+    }
+
+    #[test]
+    fn test_parse_trailing_input() {
+        let error = parse("<p>Hello</p>\n</div>\n<p>World</p>").unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEndTag);
+        assert_eq!(error.offset(), 13);
+        assert_eq!((error.line(), error.column()), (2, 1));
+
+        let error = parse("  </div><p>Hello</p>").unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEndTag);
+        assert_eq!(error.offset(), 2);
+    }
+
+    #[test]
+    fn test_parse_mismatched_end_tag() {
+        let error = parse("<div>\n  <b>\n    Hello\n  </span>\n</div>").unwrap_err();
+
+        assert_eq!(
+            error.kind(),
+            &ParseErrorKind::MismatchedEndTag {
+                expected: "b".to_string(),
+                found: "span".to_string()
+            }
+        );
+        assert_eq!((error.line(), error.column()), (4, 3));
+        assert_eq!(
+            error.to_string(),
+            "expected `</b>` but found `</span>` at line 4, column 3"
+        );
+
+        let error = parse("<div><span></div>").unwrap_err();
+
+        assert_eq!(
+            error.kind(),
+            &ParseErrorKind::MismatchedEndTag {
+                expected: "span".to_string(),
+                found: "div".to_string()
+            }
+        );
+        assert_eq!(error.offset(), 11);
+    }
+
+    #[test]
+    fn test_parse_unexpected_eof() {
+        let error = parse("<div>\n  <p>Hello</p>\n").unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::UnexpectedEof);
+        assert_eq!((error.line(), error.column()), (3, 1));
+    }
+
+    #[test]
+    fn test_parse_missing_attribute_value() {
+        for input in ["<div class =>Hello</div>", "Hello <input class= >"] {
+            let error = parse(input).unwrap_err();
+
+            assert_eq!(
+                error.kind(),
+                &ParseErrorKind::MissingAttributeValue {
+                    name: "class".to_string()
+                },
+                "{input:?}"
+            );
+        }
+
+        let error = parse("<p>\n  <a href=\n></p>").unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (3, 1));
+        assert_eq!(
+            error.kind().to_string(),
+            "missing value for attribute `href`"
+        );
+    }
+
+    #[test]
+    fn test_parse_max_depth() {
+        let nested = |depth| "<div>".repeat(depth) + "<br>" + &"</div>".repeat(depth);
+
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+
+        let error = parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+
+        assert_eq!(error.kind(), &ParseErrorKind::MaxDepthExceeded);
+        assert_eq!(error.offset(), "<div>".len() * MAX_DEPTH);
+
+        // On the stack size `MAX_DEPTH` assumes, frames are a few times larger in debug builds.
+        let stack_size = if cfg!(debug_assertions) { 4 } else { 1 } << 20;
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn(move || assert!(parse(&nested(MAX_DEPTH)).is_ok()))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        // Way deeper than the stack would allow.
+        assert!(parse(&nested(100_000)).is_err());
+
+        let options = ParseOptions {
+            max_depth: 2,
+            ..ParseOptions::default()
+        };
+
+        assert!(parse_with_options(&nested(2), &options).is_ok());
+        assert_eq!(
+            parse_with_options(&nested(3), &options)
+                .unwrap_err()
+                .offset(),
+            "<div>".len() * 2
+        );
+    }
+
+    #[test]
+    fn test_parse_iter() {
+        for input in [
+            "",
+            "  \n",
+            "<!-- comment --><p>Hello</p>",
+            "Hello <b>world</b>\n\n\n<br>\n<ul><li>a<li>b</ul> trailing text\n",
+            "---\ntitle: Home\n---\n\n<p>Hello</p>",
+            include_str!("../tests/basic.html"),
+        ] {
+            assert_eq!(
+                parse_iter(input).collect::<Result<Vec<_>, _>>(),
+                parse(input),
+                "{input:?}"
+            );
+            assert_eq!(parse_recover(input), (parse(input).unwrap(), vec![]));
+        }
+
+        let mut iter = parse_iter("<br>\n</div><br>");
+
+        assert!(matches!(iter.next(), Some(Ok(_))));
+        assert_eq!(
+            iter.next()
+                .map(|result| result.map_err(|error| error.kind().clone())),
+            Some(Err(ParseErrorKind::UnexpectedEndTag))
+        );
+        assert_eq!(iter.next(), None);
+
+        let mut iter = parse_iter("<br><div>");
+
+        assert!(matches!(iter.next(), Some(Ok(_))));
+        assert!(matches!(iter.next(), Some(Err(_))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_parse_recover() {
+        let input = "<p>before</p>\n<div><b>bold</div>\n</span><p>after</p>";
+
+        let (nodes, errors) = parse_recover(input);
+
+        let element = |name: &'static str, text: &'static str| {
+            Node::Element(Element::Normal {
+                name: name.into(),
+                attributes: vec![],
+                content: vec![Node::Text(text.into())],
+            })
+        };
+
+        assert_eq!(
+            nodes,
+            [
+                element("p", "before"),
+                Node::Text("<div>".into()),
+                Node::Text("<b>bold".into()),
+                element("p", "after"),
+            ]
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.kind().clone(), error.line(), error.column()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    ParseErrorKind::MismatchedEndTag {
+                        expected: "b".to_string(),
+                        found: "div".to_string()
+                    },
+                    2,
+                    13
+                ),
+                (ParseErrorKind::UnexpectedEndTag, 3, 1),
+            ]
+        );
+
+        assert_eq!(
+            parse_recover("<p>ok</p>"),
+            (parse("<p>ok</p>").unwrap(), vec![])
+        );
+
+        let options = ParseOptions {
+            extra_void_elements: &["my-void"],
+            ..ParseOptions::default()
+        };
+        let input = "<p><my-void>ok</p>";
+        assert_eq!(parse_recover(input).1.len(), 1);
+        assert_eq!(
+            parse_recover_with_options(input, &options),
+            (parse_with_options(input, &options).unwrap(), vec![])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_check_owned_duplicate_attributes() {
+        let attribute = |name: &str| Attribute {
+            name: String::from(name).into(),
+            value: None,
+        };
+
+        let mut nodes = vec![Node::Element(Element::Void {
+            name: "input".into(),
+            attributes: vec![attribute("disabled"), attribute("DISABLED")],
+        })];
+
+        let error = check_duplicate_attributes("<input>", &mut nodes, DuplicateAttributes::Error)
+            .unwrap_err();
+
+        assert_eq!(
+            error.kind(),
+            &ParseErrorKind::DuplicateAttribute {
+                name: String::from("DISABLED")
+            }
+        );
+        assert_eq!(error.offset(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+//! Minimal CSS-selector-style queries.
+//!
+//! Only compound selectors are supported, i.e. an optional tag name (or `*`) followed by any
+//! number of:
+//! - `.class`,
+//! - `#id`,
+//! - `[name]` (attribute presence).
+//!
+//! For example `div.active`, `#main` or `input[required]`. Combinators (e.g. `ul > li`),
+//! selector lists, attribute values and pseudo-classes are not supported.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::char,
+    combinator::{all_consuming, opt},
+    multi::many0,
+    sequence::{delimited, preceded, tuple},
+    IResult, Parser,
+};
+
+use crate::ast::element::Element;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Selector<'s> {
+    /// `None` for any tag.
+    tag: Option<&'s str>,
+    conditions: Vec<Condition<'s>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+enum Condition<'s> {
+    Class(&'s str),
+    Id(&'s str),
+    Attribute(&'s str),
+}
+
+impl<'s> Selector<'s> {
+    /// # Errors
+    /// Will return an error if `selector` is empty or uses unsupported syntax (see the module
+    /// documentation).
+    pub fn parse(selector: &'s str) -> Result<Self, SelectorError> {
+        fn identifier(input: &str) -> IResult<&str, &str> {
+            take_while1(|char: char| char.is_ascii_alphanumeric() || matches!(char, '-' | '_'))(
+                input,
+            )
+        }
+
+        let trimmed = selector.trim();
+
+        let (_, (tag_name, conditions)) = all_consuming(tuple((
+            opt(alt((tag("*").map(|_| None), identifier.map(Some)))),
+            many0(alt((
+                preceded(char('.'), identifier).map(Condition::Class),
+                preceded(char('#'), identifier).map(Condition::Id),
+                delimited(char('['), identifier, char(']')).map(Condition::Attribute),
+            ))),
+        )))
+        .parse(trimmed)
+        .map_err(|error| {
+            let leading = selector.len() - selector.trim_start().len();
+
+            SelectorError {
+                offset: match error {
+                    nom::Err::Error(error) | nom::Err::Failure(error) => {
+                        leading + trimmed.len() - error.input.len()
+                    }
+                    nom::Err::Incomplete(_) => selector.len(),
+                },
+            }
+        })?;
+
+        if tag_name.is_none() && conditions.is_empty() {
+            return Err(SelectorError { offset: 0 });
+        }
+
+        Ok(Self {
+            tag: tag_name.flatten(),
+            conditions,
+        })
+    }
+
+    pub fn matches(&self, element: &Element) -> bool {
+        let value = |name: &str| element.attr(name).flatten().unwrap_or("");
+
+        self.tag
+            .is_none_or(|tag| element.name().eq_ignore_ascii_case(tag))
+            && self.conditions.iter().all(|condition| match condition {
+                Condition::Class(class) => value("class")
+                    .split_ascii_whitespace()
+                    .any(|token| token == *class),
+                Condition::Id(id) => value("id") == *id,
+                Condition::Attribute(name) => element.has_attr(name),
+            })
+    }
+}
+
+/// A selector that is empty or uses unsupported syntax.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SelectorError {
+    offset: usize,
+}
+
+impl SelectorError {
+    /// Byte offset (in the selector) of the unsupported syntax.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported selector syntax at offset {}", self.offset)
+    }
+}
+
+impl core::error::Error for SelectorError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Node;
+
+    use super::{Selector, SelectorError};
+
+    #[test]
+    fn test_select() {
+        let (_, nodes) = Node::parse_many(concat!(
+            "<div id=\"main\" class=\"active\">\n",
+            "  <div class=\"item active\"><input required><input></div>\n",
+            "  <div class=\"inactive\"><INPUT REQUIRED=\"\"></div>\n",
+            "</div>\n",
+        ))
+        .unwrap();
+
+        let first_attributes = |selector| -> Vec<_> {
+            nodes[0]
+                .select(selector)
+                .unwrap()
+                .into_iter()
+                .map(|element| {
+                    element
+                        .attributes()
+                        .first()
+                        .map(|attribute| attribute.name.to_string())
+                })
+                .collect()
+        };
+
+        assert_eq!(first_attributes("div.active").len(), 2);
+        assert_eq!(first_attributes("#main"), [Some("id".to_string())]);
+        assert_eq!(first_attributes("input[required]").len(), 2);
+        assert_eq!(first_attributes(".item.active").len(), 1);
+        assert_eq!(first_attributes("*").len(), 6);
+        assert!(first_attributes("span").is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Selector::parse(""), Err(SelectorError { offset: 0 }));
+        assert_eq!(
+            Selector::parse(" ul > li"),
+            Err(SelectorError { offset: 3 })
+        );
+        assert_eq!(
+            Selector::parse("a[href=x]"),
+            Err(SelectorError { offset: 1 })
+        );
+        assert_eq!(
+            Selector::parse("a:hover").unwrap_err().to_string(),
+            "unsupported selector syntax at offset 1"
+        );
+    }
+}
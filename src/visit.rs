@@ -0,0 +1,210 @@
+//! Traversal of the AST.
+//!
+//! Every `visit_*` method defaults to walking its children (if any), override the ones you are
+//! interested in and call the matching `walk_*` function to keep recursing.
+
+use alloc::borrow::Cow;
+
+use crate::ast::{
+    bogus_comment::BogusComment, cdata::CData, comment::Comment, doctype::Doctype,
+    element::Element, frontmatter::Frontmatter, processing_instruction::ProcessingInstruction,
+    Node,
+};
+
+pub trait Visitor<'ast> {
+    fn visit_node(&mut self, node: &'ast Node<'ast>) {
+        walk_node(self, node);
+    }
+
+    fn visit_blank_lines(&mut self, _count: usize) {}
+
+    fn visit_bogus_comment(&mut self, _bogus_comment: &'ast BogusComment<'ast>) {}
+
+    fn visit_cdata(&mut self, _cdata: &'ast CData<'ast>) {}
+
+    fn visit_comment(&mut self, _comment: &'ast Comment<'ast>) {}
+
+    fn visit_doctype(&mut self, _doctype: &'ast Doctype<'ast>) {}
+
+    fn visit_element(&mut self, element: &'ast Element<'ast>) {
+        walk_element(self, element);
+    }
+
+    fn visit_frontmatter(&mut self, _frontmatter: &'ast Frontmatter<'ast>) {}
+
+    fn visit_processing_instruction(
+        &mut self,
+        _processing_instruction: &'ast ProcessingInstruction<'ast>,
+    ) {
+    }
+
+    fn visit_text(&mut self, _text: &'ast str) {}
+
+    fn visit_whitespace(&mut self) {}
+}
+
+pub fn walk_node<'ast, V>(visitor: &mut V, node: &'ast Node<'ast>)
+where
+    V: Visitor<'ast> + ?Sized,
+{
+    match node {
+        Node::BlankLines(count) => visitor.visit_blank_lines(*count),
+        Node::BogusComment(bogus_comment) => visitor.visit_bogus_comment(bogus_comment),
+        Node::CData(cdata) => visitor.visit_cdata(cdata),
+        Node::Comment(comment) => visitor.visit_comment(comment),
+        Node::Doctype(doctype) => visitor.visit_doctype(doctype),
+        Node::Element(element) => visitor.visit_element(element),
+        Node::Frontmatter(frontmatter) => visitor.visit_frontmatter(frontmatter),
+        Node::ProcessingInstruction(processing_instruction) => {
+            visitor.visit_processing_instruction(processing_instruction);
+        }
+        Node::Text(text) => visitor.visit_text(text),
+        Node::Whitespace => visitor.visit_whitespace(),
+    }
+}
+
+pub fn walk_nodes<'ast, V>(visitor: &mut V, nodes: &'ast [Node<'ast>])
+where
+    V: Visitor<'ast> + ?Sized,
+{
+    for node in nodes {
+        visitor.visit_node(node);
+    }
+}
+
+pub fn walk_element<'ast, V>(visitor: &mut V, element: &'ast Element<'ast>)
+where
+    V: Visitor<'ast> + ?Sized,
+{
+    walk_nodes(visitor, element.children());
+}
+
+pub trait VisitorMut<'a> {
+    fn visit_node_mut(&mut self, node: &mut Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    fn visit_blank_lines_mut(&mut self, _count: &mut usize) {}
+
+    fn visit_bogus_comment_mut(&mut self, _bogus_comment: &mut BogusComment<'a>) {}
+
+    fn visit_cdata_mut(&mut self, _cdata: &mut CData<'a>) {}
+
+    fn visit_comment_mut(&mut self, _comment: &mut Comment<'a>) {}
+
+    fn visit_doctype_mut(&mut self, _doctype: &mut Doctype<'a>) {}
+
+    fn visit_element_mut(&mut self, element: &mut Element<'a>) {
+        walk_element_mut(self, element);
+    }
+
+    fn visit_frontmatter_mut(&mut self, _frontmatter: &mut Frontmatter<'a>) {}
+
+    fn visit_processing_instruction_mut(
+        &mut self,
+        _processing_instruction: &mut ProcessingInstruction<'a>,
+    ) {
+    }
+
+    fn visit_text_mut(&mut self, _text: &mut Cow<'a, str>) {}
+
+    fn visit_whitespace_mut(&mut self) {}
+}
+
+pub fn walk_node_mut<'a, V>(visitor: &mut V, node: &mut Node<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    match node {
+        Node::BlankLines(count) => visitor.visit_blank_lines_mut(count),
+        Node::BogusComment(bogus_comment) => visitor.visit_bogus_comment_mut(bogus_comment),
+        Node::CData(cdata) => visitor.visit_cdata_mut(cdata),
+        Node::Comment(comment) => visitor.visit_comment_mut(comment),
+        Node::Doctype(doctype) => visitor.visit_doctype_mut(doctype),
+        Node::Element(element) => visitor.visit_element_mut(element),
+        Node::Frontmatter(frontmatter) => visitor.visit_frontmatter_mut(frontmatter),
+        Node::ProcessingInstruction(processing_instruction) => {
+            visitor.visit_processing_instruction_mut(processing_instruction);
+        }
+        Node::Text(text) => visitor.visit_text_mut(text),
+        Node::Whitespace => visitor.visit_whitespace_mut(),
+    }
+}
+
+pub fn walk_nodes_mut<'a, V>(visitor: &mut V, nodes: &mut [Node<'a>])
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    for node in nodes {
+        visitor.visit_node_mut(node);
+    }
+}
+
+pub fn walk_element_mut<'a, V>(visitor: &mut V, element: &mut Element<'a>)
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    walk_nodes_mut(visitor, element.children_mut());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, collections::HashMap};
+
+    use crate::{ast::element::Element, parse};
+
+    use super::{walk_element, walk_nodes, walk_nodes_mut, Visitor, VisitorMut};
+
+    #[test]
+    fn test_visitor() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct CountElements<'ast>(HashMap<&'ast str, usize>);
+
+        impl<'ast> Visitor<'ast> for CountElements<'ast> {
+            fn visit_element(&mut self, element: &'ast Element<'ast>) {
+                let (Element::Normal { name, .. } | Element::Void { name, .. }) = element;
+
+                *self.0.entry(name).or_default() += 1;
+
+                walk_element(self, element);
+            }
+        }
+
+        let nodes = parse(concat!(
+            "<ul>\n",
+            "  <li><a href=\"/\">Home</a></li>\n",
+            "  <li><a href=\"/about\">About</a><br></li>\n",
+            "</ul>\n",
+        ))?;
+
+        let mut visitor = CountElements::default();
+
+        walk_nodes(&mut visitor, &nodes);
+
+        assert_eq!(
+            visitor.0,
+            HashMap::from([("ul", 1), ("li", 2), ("a", 2), ("br", 1)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_mut() -> anyhow::Result<()> {
+        struct HideText;
+
+        impl VisitorMut<'_> for HideText {
+            fn visit_text_mut(&mut self, text: &mut Cow<'_, str>) {
+                *text = Cow::Borrowed("Hidden");
+            }
+        }
+
+        let mut nodes = parse("<div><p>Hello</p> <p>World</p></div>")?;
+
+        walk_nodes_mut(&mut HideText, &mut nodes);
+
+        assert_eq!(nodes, parse("<div><p>Hidden</p><p>Hidden</p></div>")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! Bindings for use from JavaScript, enabled by the `wasm` feature.
+//!
+//! The library builds as an rlib, build the wasm module (as a cdylib) with:
+//!
+//! ```sh
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{format, Configuration, ParseError};
+
+/// Error handed to JavaScript, `offset`, `line` & `column` are only set for parse errors.
+#[derive(serde::Serialize)]
+struct Error {
+    message: String,
+    offset: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(error: anyhow::Error) -> Self {
+        let parse_error = error.downcast_ref::<ParseError>();
+
+        Self {
+            message: format!("{error:#}"),
+            offset: parse_error.map(ParseError::offset),
+            line: parse_error.map(ParseError::line),
+            column: parse_error.map(ParseError::column),
+        }
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        serde_wasm_bindgen::to_value(&error).unwrap_or_else(|_| JsValue::from_str(&error.message))
+    }
+}
+
+/// Prettify the given input according to the configuration, `undefined` meaning the default one.
+///
+/// # Errors
+/// Will return an error object (`{ message, offset, line, column }`) if the configuration is
+/// invalid or if parsing / printing fails.
+#[wasm_bindgen(js_name = format)]
+pub fn format_wasm(input: &str, config: JsValue) -> Result<String, JsValue> {
+    let config: Configuration = if config.is_undefined() || config.is_null() {
+        Configuration::default()
+    } else {
+        serde_wasm_bindgen::from_value(config).map_err(|error| Error {
+            message: format!("invalid configuration: {error}"),
+            offset: None,
+            line: None,
+            column: None,
+        })?
+    };
+
+    format(input, &config).map_err(|error| Error::from(error).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen::JsValue;
+
+    use super::{format_wasm, Error};
+    use crate::format;
+
+    #[test]
+    fn test_format_wasm_signature() {
+        let _: fn(&str, JsValue) -> Result<String, JsValue> = format_wasm;
+    }
+
+    #[test]
+    fn test_error() {
+        let error = Error::from(format("<b>\n  </span>", &Default::default()).unwrap_err());
+
+        assert_eq!(error.offset, Some(6));
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.column, Some(3));
+    }
+}
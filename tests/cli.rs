@@ -0,0 +1,143 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+fn hast() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_hast"))
+}
+
+fn fixture(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", name]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_cli_file() {
+    let path = fixture("basic.html");
+    let expected = std::fs::read_to_string(&path).unwrap();
+
+    let output = hast().arg(&path).output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn test_cli_stdin() {
+    let mut child = hast()
+        .args(["--indent-width", "4"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"<div><p>a</p><p>b</p></div>")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "<div>\n    <p>a</p>\n    <p>b</p>\n</div>\n"
+    );
+}
+
+#[test]
+fn test_cli_check() {
+    let output = hast()
+        .arg("--check")
+        .arg(fixture("basic.html"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let mut child = hast()
+        .arg("--check")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"<div><p>a</p><p>b</p></div>")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("+  <p>a</p>"));
+}
+
+#[test]
+fn test_cli_parse_error() {
+    let mut child = hast()
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"<div>\n  <b></span>\n</div>")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("line 2, column 6"));
+}
+
+#[test]
+fn test_cli_stdin_filepath() {
+    let run = |path: &str| {
+        let mut child = hast()
+            .args(["--stdin-filepath", path])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"<p>Hello</p>\n")
+            .unwrap();
+
+        child.wait_with_output().unwrap()
+    };
+
+    let output = run("pages/index.html");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("not a document"));
+
+    let output = run("components/greeting.njk");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "<p>Hello</p>\n");
+}
@@ -0,0 +1,26 @@
+//! The parser builds (without warnings) without the `std` feature, i.e. under `no_std` +
+//! `alloc`.
+
+use std::process::Command;
+
+#[test]
+fn test_no_std_build() {
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "check",
+            "--lib",
+            "--no-default-features",
+            "--target-dir",
+            env!("CARGO_TARGET_TMPDIR"),
+        ])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("RUSTFLAGS", "-Dwarnings")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}